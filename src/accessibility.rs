@@ -0,0 +1,146 @@
+use crate::{config, type_writer::TypeWriter};
+use bevy::{prelude::*, ui::widget::measure_text_system};
+use serde::{Deserialize, Serialize};
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(config::load::<Accessibility>(Accessibility::FILE))
+            .add_systems(PreUpdate, finish_text_instantly)
+            .add_systems(PostUpdate, scale_text.before(measure_text_system));
+    }
+}
+
+pub const TEXT_SCALES: [f32; 5] = [1., 1.25, 1.5, 1.75, 2.];
+
+/// Options for players who struggle with the default presentation, persisted to
+/// [`Accessibility::FILE`].
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Show each line at once instead of typing it out.
+    pub instant_text: bool,
+    /// Press insight once to start charging and again to cancel, instead of holding it.
+    pub toggle_insight: bool,
+    /// No talking bob, parallax or screen fades.
+    pub reduced_motion: bool,
+    /// Multiplier for every UI font size, one of [`TEXT_SCALES`].
+    pub text_scale: f32,
+    /// Dim the decision box that is not focused and colour the focused one.
+    pub high_contrast: bool,
+}
+
+impl Accessibility {
+    pub const FILE: &'static str = "accessibility";
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            instant_text: false,
+            toggle_insight: false,
+            reduced_motion: false,
+            text_scale: 1.,
+            high_contrast: false,
+        }
+    }
+}
+
+fn finish_text_instantly(
+    accessibility: Res<Accessibility>,
+    type_writer: Option<ResMut<TypeWriter>>,
+) {
+    if let Some(mut type_writer) = type_writer {
+        if accessibility.instant_text && !type_writer.is_finished {
+            type_writer.finish();
+        }
+    }
+}
+
+/// The unscaled font size of a text node, taken from its first section when it is spawned.
+/// Every section is drawn at this size times [`Accessibility::text_scale`], so sections the
+/// game rewrites from an already scaled style are not scaled twice.
+#[derive(Component)]
+struct BaseFontSize(f32);
+
+/// Scales UI text by [`Accessibility::text_scale`].
+fn scale_text(
+    mut commands: Commands,
+    accessibility: Res<Accessibility>,
+    mut text: Query<(Entity, &mut Text, Option<&BaseFontSize>), With<Node>>,
+) {
+    let scale = accessibility.text_scale;
+
+    for (entity, mut text, base) in text.iter_mut() {
+        let base = match base {
+            Some(base) => base.0,
+            None => {
+                // Nothing to take the size from until the node has a section.
+                let Some(section) = text.sections.first() else {
+                    continue;
+                };
+                let base = section.style.font_size;
+                commands.entity(entity).insert(BaseFontSize(base));
+                base
+            }
+        };
+
+        let size = base * scale;
+        if text
+            .sections
+            .iter()
+            .any(|section| section.style.font_size != size)
+        {
+            for section in text.sections.iter_mut() {
+                section.style.font_size = size;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn font_sizes(world: &mut World, entity: Entity) -> Vec<f32> {
+        world.run_system_once(scale_text);
+        world
+            .get::<Text>(entity)
+            .unwrap()
+            .sections
+            .iter()
+            .map(|section| section.style.font_size)
+            .collect()
+    }
+
+    #[test]
+    fn scales_from_the_size_text_was_spawned_with() {
+        let mut world = World::new();
+        world.insert_resource(Accessibility {
+            text_scale: 1.5,
+            ..Default::default()
+        });
+        let style = TextStyle {
+            font_size: 20.,
+            ..Default::default()
+        };
+        let entity = world
+            .spawn((Text::from_section("", style), Node::default()))
+            .id();
+        assert_eq!(font_sizes(&mut world, entity), [30.]);
+
+        // Rewritten from the scaled style, as the type writer does.
+        let mut text = world.get_mut::<Text>(entity).unwrap();
+        let scaled = text.sections[0].style.clone();
+        text.sections = vec![
+            TextSection::new("a", scaled.clone()),
+            TextSection::new("b", scaled),
+        ];
+        assert_eq!(font_sizes(&mut world, entity), [30., 30.]);
+
+        world.resource_mut::<Accessibility>().text_scale = 2.;
+        assert_eq!(font_sizes(&mut world, entity), [40., 40.]);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::mixer::Bus;
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+    utils::HashMap,
+};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+pub struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<AmbienceManifest>::new(&["ambience.yaml"]))
+            .add_event::<AmbienceEvent>()
+            .add_systems(Update, (handle_ambience, fade_ambience).chain());
+    }
+}
+
+/// Every ambience profile, keyed by [`AmbienceKind`].
+///
+/// ```yaml
+/// profiles:
+///   court-night:
+///     - { path: audio/wind.mp3, volume: 1.0 }
+///     - { path: audio/cricket-chirp-56209.mp3, volume: 0.25, delay: 3.0 }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct AmbienceManifest {
+    pub profiles: HashMap<AmbienceKind, Vec<AmbienceLoop>>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct AmbienceLoop {
+    pub path: String,
+    pub volume: f32,
+    /// Seconds to wait before fading in, so that one profile can fade out first.
+    #[serde(default)]
+    pub delay: f32,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct AmbienceAssets {
+    #[asset(path = "audio/court.ambience.yaml")]
+    manifest: Handle<AmbienceManifest>,
+}
+
+/// The id of a profile in the [`AmbienceManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct AmbienceKind(Cow<'static, str>);
+
+impl AmbienceKind {
+    pub const COURT_DAY: Self = Self(Cow::Borrowed("court-day"));
+    pub const COURT_NIGHT: Self = Self(Cow::Borrowed("court-night"));
+}
+
+#[derive(Debug, Event, PartialEq)]
+pub enum AmbienceEvent {
+    /// Fades every loop to its level in the profile over the given seconds, and every loop
+    /// the profile does not have out. Zero seconds cuts straight to the profile.
+    CrossfadeTo(AmbienceKind, f32),
+    /// Like `CrossfadeTo`, but for the weather's loops, which play over the profile.
+    CrossfadeWeather(Vec<AmbienceLoop>, f32),
+    FadeOutSecs(f32),
+}
+
+/// Profiles and weather crossfade independently of each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AmbienceLayer {
+    Profile,
+    Weather,
+}
+
+/// A loop spawned by the manager, fading towards its level in the current profile.
+#[derive(Component)]
+struct AmbienceSound {
+    path: String,
+    layer: AmbienceLayer,
+    level: f32,
+    target: f32,
+    /// Level per second.
+    speed: f32,
+    delay: Timer,
+    applied: Option<f32>,
+}
+
+fn handle_ambience(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    ambience_assets: Res<AmbienceAssets>,
+    manifests: Res<Assets<AmbienceManifest>>,
+    mut reader: EventReader<AmbienceEvent>,
+    mut sounds: Query<&mut AmbienceSound>,
+) {
+    let Some(manifest) = manifests.get(&ambience_assets.manifest) else {
+        return;
+    };
+
+    for event in reader.read() {
+        let (loops, secs, layer) = match event {
+            AmbienceEvent::CrossfadeTo(kind, secs) => {
+                let Some(loops) = manifest.profiles.get(kind) else {
+                    error!("no ambience profile with id {kind:?}");
+                    continue;
+                };
+                (loops.as_slice(), *secs, Some(AmbienceLayer::Profile))
+            }
+            AmbienceEvent::CrossfadeWeather(loops, secs) => {
+                (loops.as_slice(), *secs, Some(AmbienceLayer::Weather))
+            }
+            AmbienceEvent::FadeOutSecs(secs) => (&[][..], *secs, None),
+        };
+
+        let retarget = |sound: &mut AmbienceSound, target: f32, delay: f32| {
+            sound.target = target;
+            if secs <= 0. {
+                sound.level = target;
+                sound.delay = Timer::default();
+            } else {
+                sound.speed = (target - sound.level).abs() / secs;
+                let delay = if target > sound.level { delay } else { 0. };
+                sound.delay = Timer::from_seconds(delay, TimerMode::Once);
+            }
+        };
+
+        let mut playing = Vec::new();
+        let in_layer = sounds
+            .iter_mut()
+            .filter(|sound| layer.is_none_or(|layer| sound.layer == layer));
+        for mut sound in in_layer {
+            match loops.iter().find(|l| l.path == sound.path) {
+                Some(ambience_loop) => {
+                    retarget(&mut sound, ambience_loop.volume, ambience_loop.delay);
+                    playing.push(sound.path.clone());
+                }
+                None => retarget(&mut sound, 0., 0.),
+            }
+        }
+
+        for ambience_loop in loops.iter().filter(|l| !playing.contains(&l.path)) {
+            let mut sound = AmbienceSound {
+                path: ambience_loop.path.clone(),
+                layer: layer.unwrap_or(AmbienceLayer::Profile),
+                level: 0.,
+                target: 0.,
+                speed: 0.,
+                delay: Timer::default(),
+                applied: None,
+            };
+            retarget(&mut sound, ambience_loop.volume, ambience_loop.delay);
+
+            commands.spawn((
+                Bus::Ambience.sound(
+                    server.load(ambience_loop.path.clone()),
+                    PlaybackSettings {
+                        mode: PlaybackMode::Loop,
+                        volume: Volume::new(sound.level),
+                        ..Default::default()
+                    },
+                ),
+                sound,
+            ));
+        }
+    }
+}
+
+/// Moves each loop towards its target level and despawns the loops that have faded out.
+fn fade_ambience(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sounds: Query<(Entity, &mut AmbienceSound, Option<&AudioSink>)>,
+) {
+    for (entity, mut sound, sink) in sounds.iter_mut() {
+        sound.delay.tick(time.delta());
+        if sound.delay.finished() {
+            let step = sound.speed * time.delta_seconds();
+            sound.level += (sound.target - sound.level).clamp(-step, step);
+        }
+
+        if sound.level <= 0. && sound.target <= 0. {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(sink) = sink {
+            if sound.applied != Some(sound.level) {
+                sink.set_volume(sound.level);
+                sound.applied = Some(sound.level);
+            }
+        }
+    }
+}
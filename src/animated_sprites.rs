@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+use std::time::Duration;
 
 #[derive(Component)]
 pub struct AnimationIndices {
@@ -25,6 +27,147 @@ pub fn animate_sprites(
     }
 }
 
+/// A grid sprite sheet and the named clips it contains.
+///
+/// ```yaml
+/// sprite_sheet:
+///   path: characters/images/prince_sheet.png
+///   tile_size: [64, 64]
+///   columns: 4
+///   rows: 3
+///   clips:
+///     idle: { first: 0, last: 3, frame_time: 0.2 }
+///     talk: { first: 4, last: 7, frame_time: 0.1 }
+///     react: { first: 8, last: 11, frame_time: 0.15, looping: false }
+/// ```
+#[derive(Debug, Deserialize, Clone)]
+pub struct SpriteSheet {
+    pub path: String,
+    pub tile_size: [u32; 2],
+    pub columns: u32,
+    pub rows: u32,
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+impl SpriteSheet {
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            UVec2::from(self.tile_size),
+            self.columns,
+            self.rows,
+            None,
+            None,
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy)]
+pub struct AnimationClip {
+    pub first: usize,
+    pub last: usize,
+    pub frame_time: f32,
+    #[serde(default = "looping_default")]
+    pub looping: bool,
+}
+
+fn looping_default() -> bool {
+    true
+}
+
+impl AnimationClip {
+    pub fn indices(&self) -> AnimationIndices {
+        AnimationIndices {
+            first: self.first,
+            last: self.last,
+        }
+    }
+
+    pub fn timer(&self) -> AnimationTimer {
+        AnimationTimer(Timer::from_seconds(self.frame_time, TimerMode::Repeating))
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f32(self.frame_time * (self.last + 1 - self.first) as f32)
+    }
+}
+
+pub const DEFAULT_CLIP: &str = "idle";
+
+/// Named clips of a sprite sheet. The requested clip is applied by [`update_sprite_animations`].
+///
+/// Clips that do not loop fall back to [`DEFAULT_CLIP`] once they have played through.
+#[derive(Component)]
+pub struct SpriteAnimations {
+    clips: HashMap<String, AnimationClip>,
+    current: String,
+    applied: Option<String>,
+    once: Option<Timer>,
+}
+
+impl SpriteAnimations {
+    pub fn new(clips: HashMap<String, AnimationClip>) -> Self {
+        Self {
+            clips,
+            current: DEFAULT_CLIP.into(),
+            applied: None,
+            once: None,
+        }
+    }
+
+    pub fn default_clip(&self) -> Option<&AnimationClip> {
+        self.clips.get(DEFAULT_CLIP)
+    }
+
+    /// Play the clip `name`, returning false if it does not exist.
+    pub fn play(&mut self, name: &str) -> bool {
+        if !self.clips.contains_key(name) {
+            return false;
+        }
+
+        if self.current != name {
+            self.current = name.into();
+        }
+
+        true
+    }
+}
+
+pub fn update_sprite_animations(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut SpriteAnimations,
+        &mut AnimationIndices,
+        &mut AnimationTimer,
+        &mut TextureAtlas,
+    )>,
+) {
+    for (mut animations, mut indices, mut timer, mut atlas) in &mut query {
+        if animations.applied.as_ref() == Some(&animations.current) {
+            let Some(once) = animations.once.as_mut() else {
+                continue;
+            };
+
+            once.tick(time.delta());
+            if !once.finished() {
+                continue;
+            }
+
+            animations.once = None;
+            animations.current = DEFAULT_CLIP.into();
+        }
+
+        let Some(clip) = animations.clips.get(&animations.current).copied() else {
+            continue;
+        };
+
+        *indices = clip.indices();
+        *timer = clip.timer();
+        atlas.index = clip.first;
+        animations.once = (!clip.looping).then(|| Timer::new(clip.duration(), TimerMode::Once));
+        animations.applied = Some(animations.current.clone());
+    }
+}
+
 //
 //
 //
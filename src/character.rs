@@ -1,16 +1,23 @@
-use crate::animation::set_world_to_black;
+use crate::accessibility::Accessibility;
+use crate::animated_sprites::{SpriteAnimations, SpriteSheet, DEFAULT_CLIP};
+use crate::controls::{Action, Controls};
 use crate::menu::ParallaxSprite;
 use crate::music::{MusicEvent, MusicKind};
 use crate::pixel_perfect::PIXEL_PERFECT_LAYER;
 use crate::time_state::{handle_morning, start_in_night, TimeState};
+use crate::transition::set_world_to_black;
+use crate::type_writer::{TypeWriterText, Voice};
 use crate::ui::insight::DespawnInsight;
 use crate::ui::{ActiveMask, Mask};
-use crate::{state::KingdomState, type_writer::TypeWriter, StateUpdate};
+use crate::{
+    state::{DayStart, Kingdom, KingdomState},
+    type_writer::TypeWriter,
+    StateUpdate,
+};
 use crate::{CharacterSet, GameState, SkipRemove};
 use bevy::audio::Volume;
 use bevy::{
-    ecs::system::SystemId,
-    input::{keyboard::KeyboardInput, ButtonState},
+    ecs::system::{RunSystemOnce, SystemId},
     prelude::*,
     utils::HashMap,
 };
@@ -21,7 +28,7 @@ use bevy_tweening::{
     Animator, Delay, EaseFunction, EaseMethod, RepeatCount, RepeatStrategy, Tween, TweenCompleted,
 };
 use rand::{seq::IteratorRandom, thread_rng, Rng};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sickle_ui::ui_commands::UpdateStatesExt;
 use std::time::Duration;
 
@@ -33,6 +40,7 @@ impl Plugin for CharacterPlugin {
             .insert_resource(TypeWriter::default())
             .insert_resource(ResponseResource::default())
             .insert_resource(ActiveMask(Mask::None))
+            .init_resource::<StoryRequests>()
             .add_systems(
                 OnEnter(GameState::Main),
                 (
@@ -43,6 +51,7 @@ impl Plugin for CharacterPlugin {
                     .chain(),
             )
             .add_systems(PreUpdate, load_character_sprite)
+            .add_systems(Update, update_portraits.in_set(CharacterSet))
             .add_systems(OnEnter(TimeState::Day), choose_new_character)
             .add_systems(OnEnter(TimeState::Night), choose_new_character)
             .add_systems(
@@ -65,11 +74,23 @@ fn entry_point(
     mut commands: Commands,
     server: Res<AssetServer>,
     mut event_writer: EventWriter<MusicEvent>,
-    mut state: ResMut<KingdomState>,
+    day_start: Option<Res<DayStart>>,
+    mut kingdom: Kingdom,
 ) {
+    // CONTINUE
+    if let Some(day_start) = day_start {
+        kingdom.restore(&day_start);
+        event_writer.send(MusicEvent::Play(MusicKind::DAY));
+        commands.add(|world: &mut World| {
+            world.run_system_once(set_world_to_black);
+            world.run_system_once(handle_morning);
+        });
+        return;
+    }
+
     // NIGHT STARTUP
     {
-        // event_writer.send(MusicEvent::Play(MusicKind::Dream));
+        // event_writer.send(MusicEvent::Play(MusicKind::DREAM));
         // let id = commands.register_one_shot_system(start_in_night);
         // commands.run_system(id);
     }
@@ -79,33 +100,20 @@ fn entry_point(
         // remove me
         // state.day = 1;
         // commands.next_state(TimeState::Day);
-        // event_writer.send(MusicEvent::Play(MusicKind::Day));
-    }
-
-    #[cfg(debug_assertions)]
-    {
-        state.day = 2;
-        state.wealth = 150.;
-        state.happiness = 150.;
-        let id = commands.register_one_shot_system(set_world_to_black);
-        commands.run_system(id);
-        let id = commands.register_one_shot_system(handle_morning);
-        commands.run_system(id);
-        // state.heart_size = 0.;
+        // event_writer.send(MusicEvent::Play(MusicKind::DAY));
     }
 
     // NORMAL STARTUP
-    #[cfg(not(debug_assertions))]
     {
-        event_writer.send(MusicEvent::Play(MusicKind::Day));
-        let id = commands.register_one_shot_system(set_world_to_black);
-        commands.run_system(id);
-        commands.spawn(AudioBundle {
-            source: server.load("audio/church_bells.wav"),
-            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(0.5)),
+        event_writer.send(MusicEvent::Play(MusicKind::DAY));
+        commands.spawn(crate::mixer::Bus::Sfx.sound(
+            server.load("audio/church_bells.wav"),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(0.5)),
+        ));
+        commands.add(|world: &mut World| {
+            world.run_system_once(set_world_to_black);
+            world.run_system_once(handle_morning);
         });
-        let id = commands.register_one_shot_system(handle_morning);
-        commands.run_system(id);
     }
 }
 
@@ -142,7 +150,16 @@ pub struct Characters {
     pub choose_new_character: SystemId,
 }
 
-fn load_characters(mut commands: Commands, character_assets: Res<CharacterAssets>) {
+/// Every character's requests as written, put back at the start of each reign.
+#[derive(Debug, Default, Resource)]
+pub struct StoryRequests(pub HashMap<&'static str, Vec<Vec<Request>>>);
+
+fn load_characters(
+    mut commands: Commands,
+    character_assets: Res<CharacterAssets>,
+    mut assets: ResMut<Assets<Character>>,
+    mut story_requests: ResMut<StoryRequests>,
+) {
     let mut characters = HashMap::default();
 
     characters.extend([
@@ -159,6 +176,20 @@ fn load_characters(mut commands: Commands, character_assets: Res<CharacterAssets
         ("nun", character_assets.nun.clone()),
     ]);
 
+    for (key, handle) in characters.iter() {
+        let Some(character) = assets.get_mut(handle) else {
+            continue;
+        };
+
+        match story_requests.0.get(key) {
+            Some(requests) => character.requests.clone_from(requests),
+            None => {
+                story_requests.0.insert(key, character.requests.clone());
+            }
+        }
+        character.clear_request();
+    }
+
     let choose_new_character = commands.register_one_shot_system(choose_new_character);
     commands.insert_resource(Characters {
         table: characters,
@@ -316,13 +347,11 @@ pub fn choose_new_character(
 
     active_mask.0 = request.mask;
 
-    let mut sfx = server.load("audio/interface/Wav/Cursor_tones/cursor_style_2.wav");
-    if new_character == "dream-man" {
-        sfx = server.load("audio/cursor_style_2_rev.wav");
-    }
-    *type_writer = TypeWriter::new(request.text.clone(), 0.025, sfx);
+    let voice = &character_assets.get(&new_handle).unwrap().voice;
+    *type_writer = TypeWriter::with_voice(request.text.clone(), voice, &server);
 
     let character = character_assets.get_mut(&new_handle).unwrap();
+    character.mood = active_mask.0;
     character.set_used(state.day, request_index);
     if let Some(req) = character.request(state.day) {
         response_res.yes = req.yes.text.clone();
@@ -400,15 +429,27 @@ fn load_character_sprite(
     mut reader: EventReader<AssetEvent<Character>>,
     server: Res<AssetServer>,
     mut characters: ResMut<Assets<Character>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
     for character in reader.read() {
         match character {
             AssetEvent::Added { id } => {
                 let character = characters.get_mut(*id).unwrap();
-                let head_texture =
-                    server.load(format!("{}_head.png", character.sprite_path.trim()));
-                let body_texture =
-                    server.load(format!("{}_body.png", character.sprite_path.trim()));
+                let sprite_path = character.sprite_path.trim().to_owned();
+                let head_texture = server.load(format!("{sprite_path}_head.png"));
+                character.head = head_texture.clone();
+                character.portraits = character
+                    .emotions
+                    .iter()
+                    .filter_map(|mask| {
+                        let suffix = mask.sprite_suffix()?;
+                        Some((
+                            *mask,
+                            server.load(format!("{sprite_path}_head_{suffix}.png")),
+                        ))
+                    })
+                    .collect();
+                let body_texture = server.load(format!("{sprite_path}_body.png"));
 
                 info!("adding {:?} sprite", character.name);
 
@@ -430,6 +471,22 @@ fn load_character_sprite(
                     ))
                     .id();
 
+                if let Some(sheet) = character.sprite_sheet.as_ref() {
+                    let animations = SpriteAnimations::new(sheet.clips.clone());
+                    let clip = animations.default_clip().copied().unwrap_or_default();
+
+                    commands.entity(head).insert((
+                        server.load::<Image>(sheet.path.clone()),
+                        TextureAtlas {
+                            layout: texture_atlas_layouts.add(sheet.layout()),
+                            index: clip.first,
+                        },
+                        clip.indices(),
+                        clip.timer(),
+                        animations,
+                    ));
+                }
+
                 let body = commands
                     .spawn((
                         SpriteBundle {
@@ -452,6 +509,40 @@ fn load_character_sprite(
     }
 }
 
+/// Swap each character's head for the portrait matching their current mood.
+///
+/// Animated characters play their `react` clip instead when their mood changes.
+fn update_portraits(
+    characters: Res<Assets<Character>>,
+    mut heads: Query<(&mut Handle<Image>, Option<&mut SpriteAnimations>), With<Head>>,
+    mut moods: Local<HashMap<AssetId<Character>, Mask>>,
+) {
+    for (id, character) in characters.iter() {
+        let Some([head, _]) = character.sprite else {
+            continue;
+        };
+
+        let Ok((mut texture, animations)) = heads.get_mut(head) else {
+            continue;
+        };
+
+        if let Some(mut animations) = animations {
+            if let Some(mood) = moods.insert(id, character.mood) {
+                if mood != character.mood {
+                    animations.play("react");
+                }
+            }
+
+            continue;
+        }
+
+        let portrait = character.portrait();
+        if *texture != *portrait {
+            *texture = portrait.clone();
+        }
+    }
+}
+
 #[derive(Component, PartialEq, Eq)]
 pub enum CharacterSprite {
     Head,
@@ -480,20 +571,24 @@ fn character_ui(
             &CharacterSprite,
             &mut Transform,
             Has<TalkingCharacter>,
+            Option<&mut SpriteAnimations>,
         ),
         With<SelectedCharacterSprite>,
     >,
     characters: Res<Assets<Character>>,
-    mut character_ui: Query<(&mut Text, &CharacterUi)>,
+    mut character_ui: Query<(&mut Text, &CharacterUi, Option<&mut TypeWriterText>)>,
     mut type_writer: ResMut<TypeWriter>,
-    mut reader: EventReader<KeyboardInput>,
+    controls: Controls,
     time: Res<Time>,
     mut active_mask: ResMut<ActiveMask>,
     mut masks: Query<(&mut Visibility, &Mask)>,
+    accessibility: Res<Accessibility>,
 ) {
     let Ok(selected_character) = selected_character.get_single() else {
-        for (mut text, _) in character_ui.iter_mut() {
-            text.sections[0].style.color.set_alpha(0.);
+        for (mut text, _, _) in character_ui.iter_mut() {
+            for section in text.sections.iter_mut() {
+                section.style.color.set_alpha(0.);
+            }
         }
 
         return;
@@ -510,15 +605,12 @@ fn character_ui(
     type_writer.increment(&time);
     type_writer.try_play_sound(&mut commands);
 
-    for input in reader.read() {
-        if matches!(input, KeyboardInput { key_code,  state, .. } if *key_code == KeyCode::Space && *state == ButtonState::Pressed)
-        {
-            type_writer.finish();
-        }
+    if controls.just_pressed(Action::SkipText) {
+        type_writer.finish();
     }
 
     if let Some(character) = characters.get(&selected_character.0) {
-        for (mut text, ui) in character_ui.iter_mut() {
+        for (mut text, ui, writer_text) in character_ui.iter_mut() {
             match ui {
                 CharacterUi::Name => {
                     // if selected_character.is_changed() {
@@ -527,16 +619,28 @@ fn character_ui(
                 }
                 CharacterUi::Request => {
                     text.sections[0].style.color.set_alpha(1.);
-                    text.sections[0].value = type_writer.slice_with_line_wrap().into();
+                    match writer_text {
+                        Some(mut writer_text) => {
+                            type_writer.write_sections(&mut text, &mut writer_text)
+                        }
+                        None => text.sections[0].value = type_writer.slice_with_line_wrap(),
+                    }
 
                     if !type_writer.is_finished {
-                        for (sprite, ty, transform, is_talking) in sprites.iter() {
+                        for (sprite, ty, transform, is_talking, animations) in sprites.iter_mut() {
                             if is_talking {
                                 continue;
                             }
 
                             match ty {
                                 CharacterSprite::Head => {
+                                    if animations.is_some_and(|mut a| a.play("talk"))
+                                        || accessibility.reduced_motion
+                                    {
+                                        commands.entity(sprite).insert(TalkingCharacter);
+                                        continue;
+                                    }
+
                                     let talking_tween = Tween::new(
                                         EaseMethod::Linear,
                                         Duration::from_secs_f32(0.3),
@@ -560,13 +664,21 @@ fn character_ui(
                             }
                         }
                     } else {
-                        for (sprite, ty, mut transform, is_talking) in sprites.iter_mut() {
+                        for (sprite, ty, mut transform, is_talking, animations) in
+                            sprites.iter_mut()
+                        {
                             if !is_talking {
                                 continue;
                             }
 
                             match ty {
                                 CharacterSprite::Head => {
+                                    if let Some(mut animations) = animations {
+                                        animations.play(DEFAULT_CLIP);
+                                        commands.entity(sprite).remove::<TalkingCharacter>();
+                                        continue;
+                                    }
+
                                     transform.translation.y = 0.;
 
                                     info!("removing talking animation");
@@ -593,12 +705,27 @@ pub struct Character {
     pub name: String,
     pub class: Class,
     pub sprite_path: String,
+    #[serde(default)]
+    pub voice: Voice,
+    /// Masks with their own head sprite, loaded from `{sprite_path}_head_{mask}.png`.
+    #[serde(default)]
+    pub emotions: Vec<Mask>,
+    /// Animated head, replacing the talking bob with the sheet's `talk` clip.
+    #[serde(default)]
+    pub sprite_sheet: Option<SpriteSheet>,
     pub requests: Vec<Vec<Request>>,
 
     #[serde(skip)]
     current_request: Option<usize>,
     #[serde(skip)]
     pub sprite: Option<[Entity; 2]>,
+    /// The mask this character is currently wearing.
+    #[serde(skip)]
+    pub mood: Mask,
+    #[serde(skip)]
+    head: Handle<Image>,
+    #[serde(skip)]
+    portraits: HashMap<Mask, Handle<Image>>,
 }
 
 impl Character {
@@ -626,6 +753,11 @@ impl Character {
             .and_then(|index| self.requests.get(day).map(|r| &r[index]))
     }
 
+    /// The head sprite for the current mood, falling back to the default head.
+    pub fn portrait(&self) -> &Handle<Image> {
+        self.portraits.get(&self.mood).unwrap_or(&self.head)
+    }
+
     /// Clear the current request selection. This should be called once at the start of every day.
     pub fn clear_request(&mut self) {
         self.current_request = None;
@@ -644,7 +776,7 @@ pub enum Class {
     GreaterOne,
 }
 
-#[derive(Debug, Default, Deserialize, Component, Reflect, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Component, Reflect, Clone)]
 pub struct RequestAvailability {
     pub filtered: bool,
     pub used: bool,
@@ -669,4 +801,7 @@ pub struct Request {
     pub availability: RequestAvailability,
     #[serde(default)]
     pub mask: Mask,
+    /// Can be heard again on any day of an endless reign.
+    #[serde(default)]
+    pub repeatable: bool,
 }
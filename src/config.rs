@@ -0,0 +1,72 @@
+//! Player configuration files and the save, stored as YAML in a `config` directory next to
+//! the game.
+//!
+//! On the web there is no file system, so loading falls back to the default and saving
+//! does nothing.
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(name: &str) -> std::path::PathBuf {
+    std::path::Path::new("config").join(format!("{name}.yaml"))
+}
+
+/// Loads `config/{name}.yaml`, falling back to the default if it is missing or malformed.
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = path(name);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_yaml::from_str(&contents) {
+                Ok(config) => return config,
+                Err(e) => warn!("could not parse {}: {e}", path.display()),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("could not read {}: {e}", path.display()),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    let _ = name;
+
+    T::default()
+}
+
+pub fn save<T: Serialize>(name: &str, config: &T) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = path(name);
+        let result = serde_yaml::to_string(config)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, contents).map_err(|e| e.to_string())
+            });
+
+        if let Err(e) = result {
+            error!("could not save {}: {e}", path.display());
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    let _ = (name, config);
+}
+
+/// Deletes `config/{name}.yaml`, if there is one.
+pub fn remove(name: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = path(name);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => error!("could not remove {}: {e}", path.display()),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    let _ = name;
+}
@@ -0,0 +1,323 @@
+use crate::config;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(config::load::<Bindings>(Bindings::FILE).with_missing_defaults())
+            .init_resource::<ControlsLock>()
+            .add_systems(PreUpdate, lock_controls);
+    }
+}
+
+/// Something the player can do, independent of the key or button bound to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    /// Move on to the next screen or choose the focused option.
+    Advance,
+    /// Reveal the rest of the current line at once.
+    SkipText,
+    /// Choose an option directly, counting from the left.
+    Choose(u8),
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Hold to charge insight.
+    Insight,
+    Pause,
+    OpenSettings,
+    OpenBindings,
+    Debug(DebugAction),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DebugAction {
+    PlayDream,
+    PauseMusic,
+    FadeOutMusic,
+    PlayDay,
+    Evening,
+    Stinger,
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Advance => "Advance".into(),
+            Self::SkipText => "Skip text".into(),
+            Self::Choose(n) => format!("Choose {}", n + 1),
+            Self::Left => "Left".into(),
+            Self::Right => "Right".into(),
+            Self::Up => "Up".into(),
+            Self::Down => "Down".into(),
+            Self::Insight => "Insight".into(),
+            Self::Pause => "Pause".into(),
+            Self::OpenSettings => "Settings".into(),
+            Self::OpenBindings => "Controls".into(),
+            Self::Debug(action) => format!("Debug: {action:?}"),
+        }
+    }
+}
+
+/// The keys and gamepad buttons bound to an action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Binding {
+    pub action: Action,
+    #[serde(default)]
+    pub keys: Vec<KeyCode>,
+    #[serde(default)]
+    pub buttons: Vec<GamepadButtonType>,
+}
+
+impl Binding {
+    fn new(
+        action: Action,
+        keys: impl IntoIterator<Item = KeyCode>,
+        buttons: impl IntoIterator<Item = GamepadButtonType>,
+    ) -> Self {
+        Self {
+            action,
+            keys: keys.into_iter().collect(),
+            buttons: buttons.into_iter().collect(),
+        }
+    }
+}
+
+/// Every action binding, persisted to [`Bindings::FILE`].
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bindings(pub Vec<Binding>);
+
+impl Bindings {
+    pub const FILE: &'static str = "bindings";
+
+    pub fn get(&self, action: Action) -> Option<&Binding> {
+        self.0.iter().find(|b| b.action == action)
+    }
+
+    /// Adds the default binding for any action a saved file does not mention.
+    pub fn with_missing_defaults(mut self) -> Self {
+        for binding in Self::default().0 {
+            if self.get(binding.action).is_none() {
+                self.0.push(binding);
+            }
+        }
+
+        self
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use GamepadButtonType as B;
+
+        #[allow(unused_mut)]
+        let mut bindings = vec![
+            Binding::new(
+                Action::Advance,
+                [KeyCode::Enter, KeyCode::NumpadEnter],
+                [B::South],
+            ),
+            Binding::new(
+                Action::SkipText,
+                [KeyCode::Space, KeyCode::Enter],
+                [B::South],
+            ),
+            Binding::new(Action::Choose(0), [KeyCode::Digit1], []),
+            Binding::new(Action::Choose(1), [KeyCode::Digit2], []),
+            Binding::new(
+                Action::Left,
+                [KeyCode::ArrowLeft, KeyCode::KeyA],
+                [B::DPadLeft],
+            ),
+            Binding::new(
+                Action::Right,
+                [KeyCode::ArrowRight, KeyCode::KeyD],
+                [B::DPadRight],
+            ),
+            Binding::new(Action::Up, [KeyCode::ArrowUp, KeyCode::KeyW], [B::DPadUp]),
+            Binding::new(
+                Action::Down,
+                [KeyCode::ArrowDown, KeyCode::KeyS],
+                [B::DPadDown],
+            ),
+            Binding::new(Action::Insight, [KeyCode::KeyE], [B::West, B::LeftTrigger2]),
+            Binding::new(Action::Pause, [KeyCode::Escape], [B::Start]),
+            Binding::new(Action::OpenSettings, [KeyCode::F2], [B::Select]),
+            Binding::new(Action::OpenBindings, [KeyCode::F1], []),
+        ];
+
+        #[cfg(debug_assertions)]
+        bindings.extend([
+            Binding::new(Action::Debug(DebugAction::PlayDream), [KeyCode::KeyJ], []),
+            Binding::new(Action::Debug(DebugAction::PauseMusic), [KeyCode::KeyK], []),
+            Binding::new(
+                Action::Debug(DebugAction::FadeOutMusic),
+                [KeyCode::KeyL],
+                [],
+            ),
+            Binding::new(
+                Action::Debug(DebugAction::PlayDay),
+                [KeyCode::Semicolon],
+                [],
+            ),
+            Binding::new(Action::Debug(DebugAction::Evening), [KeyCode::Quote], []),
+            Binding::new(Action::Debug(DebugAction::Stinger), [KeyCode::Comma], []),
+        ]);
+
+        Self(bindings)
+    }
+}
+
+/// While locked, gameplay reads no actions. Overlays such as the bindings screen lock the
+/// controls and read them with [`Controls::menu_just_pressed`] instead.
+#[derive(Debug, Default, Resource)]
+pub struct ControlsLock(pub bool);
+
+/// Locks the controls while this entity exists.
+#[derive(Component)]
+pub struct LocksControls;
+
+fn lock_controls(mut lock: ResMut<ControlsLock>, overlays: Query<(), With<LocksControls>>) {
+    lock.0 = !overlays.is_empty();
+}
+
+/// Action state for the court loop and menus, from both the keyboard and gamepads.
+#[derive(SystemParam)]
+pub struct Controls<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    gamepads: Res<'w, Gamepads>,
+    bindings: Res<'w, Bindings>,
+    lock: Res<'w, ControlsLock>,
+}
+
+impl Controls<'_> {
+    fn check(
+        &self,
+        action: Action,
+        keys: impl Fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        buttons: impl Fn(&ButtonInput<GamepadButton>, GamepadButton) -> bool,
+    ) -> bool {
+        let Some(binding) = self.bindings.get(action) else {
+            return false;
+        };
+
+        binding.keys.iter().any(|key| keys(&self.keys, *key))
+            || self.gamepads.iter().any(|gamepad| {
+                binding
+                    .buttons
+                    .iter()
+                    .any(|ty| buttons(&self.buttons, GamepadButton::new(gamepad, *ty)))
+            })
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.lock.0
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        !self.is_locked() && self.menu_just_pressed(action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        !self.is_locked()
+            && self.check(
+                action,
+                |input, key| input.just_released(key),
+                |input, button| input.just_released(button),
+            )
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        !self.is_locked()
+            && self.check(
+                action,
+                |input, key| input.pressed(key),
+                |input, button| input.pressed(button),
+            )
+    }
+
+    /// Like [`Controls::just_pressed`], but ignores the [`ControlsLock`].
+    pub fn menu_just_pressed(&self, action: Action) -> bool {
+        self.check(
+            action,
+            |input, key| input.just_pressed(key),
+            |input, button| input.just_pressed(button),
+        )
+    }
+
+    /// Any key or gamepad button, for "press any key" screens.
+    pub fn any(&self) -> bool {
+        !self.is_locked()
+            && (self.keys.get_just_pressed().next().is_some()
+                || self.buttons.get_just_pressed().next().is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAVED: &str = "
+- action: Advance
+  keys: [Space]
+- action: !Choose 0
+  keys: [Digit3]
+- action: Insight
+  buttons: [RightTrigger2]
+";
+
+    #[test]
+    fn parses_saved_bindings() {
+        let bindings: Bindings = serde_yaml::from_str(SAVED).unwrap();
+
+        assert_eq!(
+            bindings.get(Action::Advance).unwrap().keys,
+            [KeyCode::Space]
+        );
+        assert_eq!(
+            bindings.get(Action::Choose(0)).unwrap().keys,
+            [KeyCode::Digit3]
+        );
+        let insight = bindings.get(Action::Insight).unwrap();
+        assert!(insight.keys.is_empty());
+        assert_eq!(insight.buttons, [GamepadButtonType::RightTrigger2]);
+    }
+
+    #[test]
+    fn fills_in_missing_defaults() {
+        let bindings = serde_yaml::from_str::<Bindings>(SAVED)
+            .unwrap()
+            .with_missing_defaults();
+
+        assert_eq!(
+            bindings.get(Action::Advance).unwrap().keys,
+            [KeyCode::Space]
+        );
+        assert_eq!(bindings.get(Action::Pause).unwrap().keys, [KeyCode::Escape]);
+        for binding in Bindings::default().0 {
+            assert!(bindings.get(binding.action).is_some());
+        }
+    }
+
+    #[test]
+    fn round_trips_the_defaults() {
+        let yaml = serde_yaml::to_string(&Bindings::default()).unwrap();
+        let bindings: Bindings = serde_yaml::from_str(&yaml).unwrap();
+
+        for binding in Bindings::default().0 {
+            let parsed = bindings.get(binding.action).unwrap();
+            assert_eq!(parsed.keys, binding.keys);
+            assert_eq!(parsed.buttons, binding.buttons);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_actions() {
+        assert!(serde_yaml::from_str::<Bindings>("- action: Fly").is_err());
+    }
+}
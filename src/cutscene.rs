@@ -0,0 +1,643 @@
+use crate::{
+    ambience::{AmbienceEvent, AmbienceKind},
+    controls::Controls,
+    end::reset_game,
+    menu::ParallaxSprite,
+    mixer::Bus,
+    particles::Particles,
+    scenery::{SceneryKind, ShowScenery},
+    state::{KingdomState, MAX_PROSPERITY},
+    type_writer::{TypeWriter, TypeWriterText},
+    ui::FONT_PATH,
+    GameState,
+};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    ecs::system::{RunSystemOnce, SystemParam},
+    input::{keyboard::KeyboardInput, ButtonState},
+    prelude::*,
+    utils::HashMap,
+};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use bevy_tweening::{
+    lens::{TextColorLens, TransformPositionLens, TransformScaleLens},
+    Animator, EaseFunction, EaseMethod, Tween,
+};
+use serde::Deserialize;
+use sickle_ui::ui_commands::UpdateStatesExt;
+use std::{borrow::Cow, collections::VecDeque, time::Duration};
+
+pub struct CutscenePlugin;
+
+impl Plugin for CutscenePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<CutsceneManifest>::new(&[
+            "cutscenes.yaml",
+        ]))
+        .add_event::<PlayCutscene>()
+        .init_resource::<CutscenePlayer>()
+        .add_systems(Update, (start_cutscenes, play_cutscenes).chain());
+    }
+}
+
+/// Scripted sequences, keyed by [`CutsceneKind`]. Tracks play side by side, and the actions
+/// within a track run one after another.
+///
+/// ```yaml
+/// cutscenes:
+///   revolution:
+///     - - !text { actor: narration, left: 10, top: 80, font_size: 50 }
+///       - !typewriter { actor: narration, line: "Alas, dear King.", secs_per_char: 0.035 }
+///       - !wait_for_input { timeout: 10 }
+///       - return_to_menu
+///     - - !wait 5
+///       - !state revolution
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct CutsceneManifest {
+    pub cutscenes: HashMap<CutsceneKind, Vec<Vec<CutsceneAction>>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CutsceneAction {
+    /// Seconds before the track's next action.
+    Wait(f32),
+    /// Holds the track until a key or button is pressed, or `timeout` seconds pass. A press
+    /// while a typewriter line is still being written finishes the line instead.
+    WaitForInput {
+        #[serde(default)]
+        timeout: Option<f32>,
+    },
+    Sprite {
+        #[serde(default)]
+        actor: Option<String>,
+        texture: String,
+        #[serde(default)]
+        position: Vec3,
+        #[serde(default)]
+        parallax: Option<f32>,
+    },
+    /// A UI text node, placed in percent of the screen. `{prosperity}`, `{max_prosperity}`
+    /// and `{reign}` in the value are filled in from the kingdom.
+    Text {
+        actor: String,
+        #[serde(default)]
+        value: String,
+        left: f32,
+        top: f32,
+        font_size: f32,
+        #[serde(default = "alpha_default")]
+        alpha: f32,
+    },
+    Tween {
+        actor: String,
+        secs: f32,
+        #[serde(default)]
+        ease: CutsceneEase,
+        lens: CutsceneLens,
+    },
+    Show(String),
+    Hide(String),
+    Despawn(String),
+    Sound {
+        #[serde(default)]
+        actor: Option<String>,
+        path: String,
+        #[serde(default = "bus_default")]
+        bus: Bus,
+        #[serde(default = "volume_default")]
+        volume: f32,
+        #[serde(default = "speed_default")]
+        speed: f32,
+        #[serde(default)]
+        looped: bool,
+    },
+    Particles {
+        #[serde(default)]
+        actor: Option<String>,
+        preset: String,
+        #[serde(default)]
+        position: Vec3,
+    },
+    Scenery(SceneryKind),
+    Ambience {
+        kind: AmbienceKind,
+        secs: f32,
+    },
+    /// Writes `line` into a [`CutsceneAction::Text`] actor.
+    Typewriter {
+        actor: String,
+        line: String,
+        secs_per_char: f32,
+        #[serde(default = "blip_default")]
+        sfx: String,
+    },
+    State(GameState),
+    /// Clears the game away and goes back to the main menu.
+    ReturnToMenu,
+}
+
+fn alpha_default() -> f32 {
+    1.
+}
+
+fn bus_default() -> Bus {
+    Bus::Sfx
+}
+
+fn volume_default() -> f32 {
+    1.
+}
+
+fn speed_default() -> f32 {
+    1.
+}
+
+/// Whether `secs` from a cutscene file can be waited out.
+fn is_valid_secs(secs: f32) -> bool {
+    secs.is_finite() && secs >= 0.
+}
+
+fn blip_default() -> String {
+    "audio/cursor_style_2_rev.wav".into()
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CutsceneEase {
+    #[default]
+    Linear,
+    QuadraticIn,
+    QuadraticOut,
+    QuadraticInOut,
+}
+
+impl From<CutsceneEase> for EaseMethod {
+    fn from(ease: CutsceneEase) -> Self {
+        match ease {
+            CutsceneEase::Linear => EaseMethod::Linear,
+            CutsceneEase::QuadraticIn => EaseFunction::QuadraticIn.into(),
+            CutsceneEase::QuadraticOut => EaseFunction::QuadraticOut.into(),
+            CutsceneEase::QuadraticInOut => EaseFunction::QuadraticInOut.into(),
+        }
+    }
+}
+
+/// What a [`CutsceneAction::Tween`] animates. Leaving out `from` starts at the actor's
+/// current value.
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CutsceneLens {
+    Move {
+        #[serde(default)]
+        from: Option<Vec3>,
+        to: Vec3,
+    },
+    Scale {
+        #[serde(default)]
+        from: Option<f32>,
+        to: f32,
+    },
+    /// Multiplies the current scale.
+    Grow(f32),
+    /// Fades a text actor's first section.
+    FadeText { from: f32, to: f32 },
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct CutsceneAssets {
+    #[asset(path = "cutscenes/end.cutscenes.yaml")]
+    manifest: Handle<CutsceneManifest>,
+}
+
+/// The id of a cutscene in the [`CutsceneManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct CutsceneKind(Cow<'static, str>);
+
+impl CutsceneKind {
+    pub const WIN: Self = Self(Cow::Borrowed("win"));
+    pub const REVOLUTION: Self = Self(Cow::Borrowed("revolution"));
+    pub const DEATH_SWOLLEN: Self = Self(Cow::Borrowed("death-swollen"));
+    pub const DEATH_WITHERED: Self = Self(Cow::Borrowed("death-withered"));
+}
+
+/// Replaces the playing cutscene, if any, with the given one.
+#[derive(Debug, Event)]
+pub struct PlayCutscene {
+    kind: CutsceneKind,
+    actors: Vec<(String, Entity)>,
+}
+
+impl PlayCutscene {
+    pub fn new(kind: CutsceneKind) -> Self {
+        Self {
+            kind,
+            actors: Vec::new(),
+        }
+    }
+
+    /// Lets the cutscene refer to an entity it did not spawn.
+    pub fn with_actor(mut self, name: &str, entity: Entity) -> Self {
+        self.actors.push((name.into(), entity));
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CutscenePlayer {
+    playing: Option<Playing>,
+}
+
+impl CutscenePlayer {
+    pub fn stop(&mut self) {
+        self.playing = None;
+    }
+}
+
+struct Playing {
+    tracks: Vec<Track>,
+    actors: HashMap<String, Entity>,
+    /// The text actor the typewriter writes into.
+    narration: Option<Entity>,
+}
+
+struct Track {
+    actions: VecDeque<CutsceneAction>,
+    hold: Hold,
+}
+
+enum Hold {
+    None,
+    Secs(f32),
+    Input { timeout: Option<f32> },
+}
+
+impl Track {
+    fn is_released(&self, pressed: bool) -> bool {
+        match self.hold {
+            Hold::None => true,
+            Hold::Secs(secs) => secs <= 0.,
+            Hold::Input { timeout } => pressed || timeout.is_some_and(|timeout| timeout <= 0.),
+        }
+    }
+}
+
+fn start_cutscenes(
+    mut reader: EventReader<PlayCutscene>,
+    cutscene_assets: Res<CutsceneAssets>,
+    manifests: Res<Assets<CutsceneManifest>>,
+    mut player: ResMut<CutscenePlayer>,
+) {
+    let Some(PlayCutscene { kind, actors }) = reader.read().last() else {
+        return;
+    };
+
+    let Some(tracks) = manifests
+        .get(&cutscene_assets.manifest)
+        .and_then(|manifest| manifest.cutscenes.get(kind))
+    else {
+        error!("no cutscene with id {kind:?}");
+        return;
+    };
+
+    player.playing = Some(Playing {
+        tracks: tracks
+            .iter()
+            .map(|actions| Track {
+                actions: actions.iter().cloned().collect(),
+                hold: Hold::None,
+            })
+            .collect(),
+        actors: actors.iter().cloned().collect(),
+        narration: None,
+    });
+}
+
+/// Presses that can move a cutscene on.
+#[derive(SystemParam)]
+struct CutsceneInput<'w, 's> {
+    reader: EventReader<'w, 's, KeyboardInput>,
+    controls: Controls<'w>,
+}
+
+impl CutsceneInput<'_, '_> {
+    fn pressed(&mut self) -> bool {
+        let locked = self.controls.is_locked();
+        // Always drain the reader, so presses from before a wait do not count towards it.
+        let pressed = self
+            .reader
+            .read()
+            .filter(|input| input.state == ButtonState::Pressed && !locked)
+            .count()
+            > 0;
+        pressed || self.controls.any()
+    }
+}
+
+/// Everything cutscene actions act on.
+#[derive(SystemParam)]
+struct Stage<'w, 's> {
+    commands: Commands<'w, 's>,
+    server: Res<'w, AssetServer>,
+    particles: Particles<'w>,
+    scenery: EventWriter<'w, ShowScenery>,
+    ambience: EventWriter<'w, AmbienceEvent>,
+    type_writer: ResMut<'w, TypeWriter>,
+    state: Res<'w, KingdomState>,
+    transforms: Query<'w, 's, &'static Transform>,
+    visibility: Query<'w, 's, &'static mut Visibility>,
+    narration: Query<'w, 's, (&'static mut Text, &'static mut TypeWriterText)>,
+}
+
+impl Stage<'_, '_> {
+    fn perform(&mut self, playing: &mut Playing, action: CutsceneAction) -> Hold {
+        match action {
+            CutsceneAction::Wait(secs) => {
+                if !is_valid_secs(secs) {
+                    error!("invalid cutscene wait of {secs} seconds");
+                    return Hold::None;
+                }
+                return Hold::Secs(secs);
+            }
+            CutsceneAction::WaitForInput { timeout } => {
+                if let Some(secs) = timeout.filter(|secs| !is_valid_secs(*secs)) {
+                    error!("invalid cutscene input timeout of {secs} seconds");
+                    return Hold::None;
+                }
+                return Hold::Input { timeout };
+            }
+            CutsceneAction::Sprite {
+                actor,
+                texture,
+                position,
+                parallax,
+            } => {
+                let mut entity = self.commands.spawn(SpriteBundle {
+                    texture: self.server.load(texture),
+                    transform: Transform::from_translation(position),
+                    ..Default::default()
+                });
+                if let Some(parallax) = parallax {
+                    entity.insert(ParallaxSprite(parallax));
+                }
+                if let Some(actor) = actor {
+                    playing.actors.insert(actor, entity.id());
+                }
+            }
+            CutsceneAction::Text {
+                actor,
+                value,
+                left,
+                top,
+                font_size,
+                alpha,
+            } => {
+                let value = value
+                    .replace("{prosperity}", &self.state.prosperity().to_string())
+                    .replace("{max_prosperity}", &MAX_PROSPERITY.to_string())
+                    .replace("{reign}", &self.state.reign_summary());
+                let entity = self.commands.spawn(
+                    TextBundle::from_section(
+                        value,
+                        TextStyle {
+                            font: self.server.load(FONT_PATH),
+                            font_size,
+                            color: Color::WHITE.with_alpha(alpha),
+                        },
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(left),
+                        top: Val::Percent(top),
+                        ..Default::default()
+                    })
+                    .with_text_justify(JustifyText::Left),
+                );
+                playing.actors.insert(actor, entity.id());
+            }
+            CutsceneAction::Tween {
+                actor,
+                secs,
+                ease,
+                lens,
+            } => {
+                if !is_valid_secs(secs) {
+                    error!("invalid cutscene tween of {secs} seconds on {actor}");
+                    return Hold::None;
+                }
+                let Some(&entity) = playing.actors.get(&actor) else {
+                    error!("no cutscene actor named {actor}");
+                    return Hold::None;
+                };
+                let current = self.transforms.get(entity).copied().unwrap_or_default();
+                let duration = Duration::from_secs_f32(secs);
+
+                let mut entity = self.commands.entity(entity);
+                match lens {
+                    CutsceneLens::Move { from, to } => {
+                        entity.insert(Animator::new(Tween::new(
+                            ease,
+                            duration,
+                            TransformPositionLens {
+                                start: from.unwrap_or(current.translation),
+                                end: to,
+                            },
+                        )));
+                    }
+                    CutsceneLens::Scale { from, to } => {
+                        entity.insert(Animator::new(Tween::new(
+                            ease,
+                            duration,
+                            TransformScaleLens {
+                                start: from.map_or(current.scale, Vec3::splat),
+                                end: Vec3::splat(to),
+                            },
+                        )));
+                    }
+                    CutsceneLens::Grow(by) => {
+                        entity.insert(Animator::new(Tween::new(
+                            ease,
+                            duration,
+                            TransformScaleLens {
+                                start: current.scale,
+                                end: current.scale * by,
+                            },
+                        )));
+                    }
+                    CutsceneLens::FadeText { from, to } => {
+                        entity.insert(Animator::new(Tween::new(
+                            ease,
+                            duration,
+                            TextColorLens {
+                                start: Color::WHITE.with_alpha(from),
+                                end: Color::WHITE.with_alpha(to),
+                                section: 0,
+                            },
+                        )));
+                    }
+                }
+            }
+            CutsceneAction::Show(actor) => {
+                self.set_visibility(playing, &actor, Visibility::Visible)
+            }
+            CutsceneAction::Hide(actor) => self.set_visibility(playing, &actor, Visibility::Hidden),
+            CutsceneAction::Despawn(actor) => match playing.actors.remove(&actor) {
+                Some(entity) => self.commands.entity(entity).despawn_recursive(),
+                None => error!("no cutscene actor named {actor}"),
+            },
+            CutsceneAction::Sound {
+                actor,
+                path,
+                bus,
+                volume,
+                speed,
+                looped,
+            } => {
+                let entity = self.commands.spawn(bus.sound(
+                    self.server.load(path),
+                    PlaybackSettings {
+                        mode: if looped {
+                            PlaybackMode::Loop
+                        } else {
+                            PlaybackMode::Despawn
+                        },
+                        volume: Volume::new(volume),
+                        speed,
+                        ..Default::default()
+                    },
+                ));
+                if let Some(actor) = actor {
+                    playing.actors.insert(actor, entity.id());
+                }
+            }
+            CutsceneAction::Particles {
+                actor,
+                preset,
+                position,
+            } => {
+                let transform = Transform::from_translation(position);
+                if let Some(entity) = self.particles.spawn(&mut self.commands, &preset, transform) {
+                    if let Some(actor) = actor {
+                        playing.actors.insert(actor, entity.id());
+                    }
+                }
+            }
+            CutsceneAction::Scenery(kind) => {
+                self.scenery.send(ShowScenery(kind));
+            }
+            CutsceneAction::Ambience { kind, secs } => {
+                self.ambience.send(AmbienceEvent::CrossfadeTo(kind, secs));
+            }
+            CutsceneAction::Typewriter {
+                actor,
+                line,
+                secs_per_char,
+                sfx,
+            } => {
+                if !is_valid_secs(secs_per_char) || secs_per_char == 0. {
+                    error!(
+                        "invalid cutscene typewriter speed of {secs_per_char} seconds on {actor}"
+                    );
+                    return Hold::None;
+                }
+                let Some(&entity) = playing.actors.get(&actor) else {
+                    error!("no cutscene actor named {actor}");
+                    return Hold::None;
+                };
+                if self.narration.get(entity).is_err() {
+                    self.commands
+                        .entity(entity)
+                        .insert(TypeWriterText::default());
+                }
+                playing.narration = Some(entity);
+                *self.type_writer = TypeWriter::new(line, secs_per_char, self.server.load(sfx));
+            }
+            CutsceneAction::State(state) => {
+                self.commands.next_state(state);
+            }
+            CutsceneAction::ReturnToMenu => {
+                self.commands
+                    .add(|world: &mut World| world.run_system_once(reset_game));
+            }
+        }
+
+        Hold::None
+    }
+
+    fn set_visibility(&mut self, playing: &Playing, actor: &str, visibility: Visibility) {
+        match playing
+            .actors
+            .get(actor)
+            .and_then(|&entity| self.visibility.get_mut(entity).ok())
+        {
+            Some(mut current) => *current = visibility,
+            None => error!("no cutscene actor named {actor}"),
+        }
+    }
+
+    fn write_narration(&mut self, playing: &Playing, time: &Time) {
+        let Some((mut text, mut writer_text)) = playing
+            .narration
+            .and_then(|entity| self.narration.get_mut(entity).ok())
+        else {
+            return;
+        };
+
+        self.type_writer.increment(time);
+        self.type_writer.try_play_sound(&mut self.commands);
+        self.type_writer.write_sections(&mut text, &mut writer_text);
+    }
+}
+
+fn play_cutscenes(
+    mut player: ResMut<CutscenePlayer>,
+    time: Res<Time>,
+    mut input: CutsceneInput,
+    mut stage: Stage,
+) {
+    let mut pressed = input.pressed();
+    let Some(playing) = player.playing.as_mut() else {
+        return;
+    };
+
+    // One press either finishes the line being written or releases the tracks waiting on it.
+    if pressed && playing.narration.is_some() && !stage.type_writer.is_finished {
+        stage.type_writer.finish();
+        pressed = false;
+    }
+
+    let mut tracks = std::mem::take(&mut playing.tracks);
+    for track in tracks.iter_mut() {
+        match &mut track.hold {
+            Hold::Secs(secs) => *secs -= time.delta_seconds(),
+            Hold::Input {
+                timeout: Some(timeout),
+            } => *timeout -= time.delta_seconds(),
+            _ => {}
+        }
+
+        while track.is_released(pressed) {
+            let Some(action) = track.actions.pop_front() else {
+                track.hold = Hold::None;
+                break;
+            };
+            track.hold = stage.perform(playing, action);
+
+            // The press that released this track is not the one it now waits for.
+            if matches!(track.hold, Hold::Input { .. }) {
+                break;
+            }
+        }
+    }
+    tracks.retain(|track| !track.actions.is_empty() || !matches!(track.hold, Hold::None));
+    playing.tracks = tracks;
+
+    stage.write_narration(playing, &time);
+
+    if playing.tracks.is_empty() {
+        player.stop();
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use serde::{Deserialize, Serialize};
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<DifficultyManifest>::new(&[
+            "difficulty.yaml",
+        ]))
+        .init_resource::<Difficulty>();
+    }
+}
+
+/// The difficulty picked for the next new game.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Resource, Reflect, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Story,
+    #[default]
+    Normal,
+    Tyrant,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Story, Difficulty::Normal, Difficulty::Tyrant];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Story => "Story",
+            Self::Normal => "Normal",
+            Self::Tyrant => "Tyrant",
+        }
+    }
+}
+
+/// The rules of each difficulty.
+///
+/// ```yaml
+/// normal:
+///   description: The reign as it was meant to be.
+///   heart_size: 3
+///   wealth: 50
+///   happiness: 50
+///   min_heart_size: 0
+///   max_heart_size: 6
+///   min_prosperity: 150
+///   insight_charge_time: 2
+///   delta_multiplier: 1
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct DifficultyManifest {
+    pub story: DifficultyProfile,
+    pub normal: DifficultyProfile,
+    pub tyrant: DifficultyProfile,
+}
+
+impl DifficultyManifest {
+    pub fn profile(&self, difficulty: Difficulty) -> &DifficultyProfile {
+        match difficulty {
+            Difficulty::Story => &self.story,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Tyrant => &self.tyrant,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Reflect, Serialize, Deserialize)]
+pub struct DifficultyProfile {
+    /// Shown while the difficulty is picked.
+    pub description: String,
+    /// The stats a reign starts with.
+    pub heart_size: f32,
+    pub wealth: f32,
+    pub happiness: f32,
+    /// The heart gives out at or past either bound.
+    pub min_heart_size: f32,
+    pub max_heart_size: f32,
+    /// The prosperity needed by the end of the reign to win.
+    pub min_prosperity: f32,
+    /// Seconds insight has to be held before it is granted.
+    pub insight_charge_time: f32,
+    /// Scales how much each decision moves the heart, wealth and happiness.
+    pub delta_multiplier: f32,
+}
+
+impl DifficultyProfile {
+    /// How far a heart of `size` is from the lower bound to the upper one.
+    pub fn heart_fraction(&self, size: f32) -> f32 {
+        let span = self.max_heart_size - self.min_heart_size;
+        if span <= 0. {
+            return 0.5;
+        }
+
+        (size - self.min_heart_size) / span
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct DifficultyAssets {
+    #[asset(path = "difficulty/court.difficulty.yaml")]
+    manifest: Handle<DifficultyManifest>,
+}
+
+/// Looks up the profile of each difficulty.
+#[derive(SystemParam)]
+pub struct Difficulties<'w> {
+    difficulty_assets: Res<'w, DifficultyAssets>,
+    manifests: Res<'w, Assets<DifficultyManifest>>,
+}
+
+impl Difficulties<'_> {
+    pub fn profile(&self, difficulty: Difficulty) -> Option<&DifficultyProfile> {
+        let manifest = self.manifests.get(&self.difficulty_assets.manifest);
+        if manifest.is_none() {
+            error!("the difficulty manifest is not loaded");
+        }
+
+        manifest.map(|manifest| manifest.profile(difficulty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_court_manifest() {
+        let manifest: DifficultyManifest =
+            serde_yaml::from_str(include_str!("../assets/difficulty/court.difficulty.yaml"))
+                .unwrap();
+
+        for difficulty in Difficulty::ALL {
+            let profile = manifest.profile(difficulty);
+            assert!(!profile.description.is_empty(), "{difficulty:?}");
+            assert!(
+                profile.min_heart_size < profile.heart_size
+                    && profile.heart_size < profile.max_heart_size,
+                "{difficulty:?} starts with its heart out of bounds"
+            );
+            assert!(profile.delta_multiplier > 0., "{difficulty:?}");
+        }
+
+        let (story, tyrant) = (&manifest.story, &manifest.tyrant);
+        assert!(story.min_prosperity < tyrant.min_prosperity);
+        assert!(story.delta_multiplier < tyrant.delta_multiplier);
+    }
+
+    #[test]
+    fn places_the_heart_between_its_bounds() {
+        let profile = DifficultyProfile {
+            min_heart_size: 1.,
+            max_heart_size: 5.,
+            ..Default::default()
+        };
+
+        assert_eq!(profile.heart_fraction(1.), 0.);
+        assert_eq!(profile.heart_fraction(3.), 0.5);
+        assert_eq!(profile.heart_fraction(5.), 1.);
+        assert_eq!(DifficultyProfile::default().heart_fraction(3.), 0.5);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::{
+    character::{choose_new_character, Character, Characters, Request, StoryRequests},
+    state::{remember_day_start, KingdomState, NewHeartSize, StateUpdate},
+    time_state::TimeState,
+};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub struct EndlessPlugin;
+
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<EndlessManifest>::new(&["endless.yaml"]))
+            .init_resource::<Reign>()
+            .add_systems(
+                OnEnter(TimeState::Day),
+                plan_day
+                    .run_if(is_endless)
+                    .before(remember_day_start)
+                    .before(choose_new_character),
+            )
+            .add_systems(OnEnter(TimeState::Morning), raise_stakes.run_if(is_endless));
+    }
+}
+
+/// How the reign plays out, picked for the next new game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Resource, Reflect, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reign {
+    /// Three seasons, won on prosperity.
+    #[default]
+    Seasons,
+    /// Days keep coming until the heart or the kingdom gives out. Each day's petitioners
+    /// are drawn from `seed`, so a restarted day plays the same.
+    Endless { seed: u64 },
+}
+
+fn is_endless(state: Res<KingdomState>) -> bool {
+    state.is_endless()
+}
+
+/// How the endless reign's days are filled and how its stakes rise.
+///
+/// ```yaml
+/// delta_growth: 0.1
+/// heart_narrowing: 0.25
+/// petitioners: 4
+/// petitioner_growth: 0.5
+/// max_petitioners: 8
+/// generated_chance: 0.5
+/// words:
+///   place: [mill, bridge]
+/// generated:
+///   - text: The {place} has collapsed. Will the crown rebuild it?
+///     yes: { wealth: [-12, -6], happiness: [4, 8] }
+///     no: { happiness: [-8, -4] }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct EndlessManifest {
+    /// Added to the delta multiplier each morning.
+    pub delta_growth: f32,
+    /// How far each heart bound closes in each morning.
+    pub heart_narrowing: f32,
+    /// Petitioners heard on the first day.
+    pub petitioners: f32,
+    /// Petitioners added each day after.
+    pub petitioner_growth: f32,
+    /// Each character is heard at most once a day, so more than there are characters is
+    /// no different.
+    pub max_petitioners: usize,
+    /// The chance a petitioner brings a generated request rather than a repeatable one.
+    pub generated_chance: f64,
+    /// Filled into `{word}` placeholders in generated requests.
+    #[serde(default)]
+    pub words: HashMap<String, Vec<String>>,
+    pub generated: Vec<GeneratedRequest>,
+}
+
+impl EndlessManifest {
+    fn petitioners(&self, day: usize) -> usize {
+        ((self.petitioners + self.petitioner_growth * day as f32) as usize)
+            .min(self.max_petitioners)
+    }
+
+    /// Replaces each `{word}` in `text` with one of its words.
+    fn fill(&self, text: &str, rng: &mut impl Rng) -> String {
+        let mut filled = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+
+            filled.push_str(&rest[..start]);
+            let key = &rest[start + 1..end];
+            match self.words.get(key).and_then(|words| words.choose(rng)) {
+                Some(word) => filled.push_str(word),
+                None => {
+                    warn!("no words for '{{{key}}}' in the endless manifest");
+                    filled.push_str(&rest[start..=end]);
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        filled.push_str(rest);
+
+        filled
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GeneratedRequest {
+    pub text: String,
+    pub yes: GeneratedUpdate,
+    pub no: GeneratedUpdate,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GeneratedUpdate {
+    pub text: Option<String>,
+    pub last_word: Option<String>,
+    /// The smallest and largest change, rounded to whole numbers.
+    pub heart_size: [f32; 2],
+    pub wealth: [f32; 2],
+    pub happiness: [f32; 2],
+}
+
+impl GeneratedUpdate {
+    fn sample(&self, rng: &mut impl Rng) -> StateUpdate {
+        let mut between = |[low, high]: [f32; 2]| {
+            if low < high {
+                rng.gen_range(low..=high).round()
+            } else {
+                low
+            }
+        };
+
+        StateUpdate {
+            text: self.text.clone(),
+            heart_size: between(self.heart_size),
+            wealth: between(self.wealth),
+            happiness: between(self.happiness),
+            last_word: self.last_word.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl GeneratedRequest {
+    fn request(&self, manifest: &EndlessManifest, rng: &mut impl Rng) -> Request {
+        Request {
+            text: manifest.fill(&self.text, rng),
+            yes: self.yes.sample(rng),
+            no: self.no.sample(rng),
+            filter: None,
+            response_handlers: Vec::new(),
+            availability: Default::default(),
+            mask: Default::default(),
+            repeatable: false,
+        }
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct EndlessAssets {
+    #[asset(path = "endless/court.endless.yaml")]
+    manifest: Handle<EndlessManifest>,
+}
+
+#[derive(SystemParam)]
+pub struct Endless<'w> {
+    endless_assets: Res<'w, EndlessAssets>,
+    manifests: Res<'w, Assets<EndlessManifest>>,
+}
+
+impl Endless<'_> {
+    fn manifest(&self) -> Option<&EndlessManifest> {
+        let manifest = self.manifests.get(&self.endless_assets.manifest);
+        if manifest.is_none() {
+            error!("the endless manifest is not loaded");
+        }
+
+        manifest
+    }
+}
+
+/// Fills the day's requests with petitioners, each bringing one of their repeatable
+/// requests or a generated one. The dream is only part of the seasons.
+fn plan_day(
+    state: Res<KingdomState>,
+    endless: Endless,
+    characters: Res<Characters>,
+    story_requests: Res<StoryRequests>,
+    mut character_assets: ResMut<Assets<Character>>,
+) {
+    let Reign::Endless { seed } = state.reign else {
+        return;
+    };
+    let Some(manifest) = endless.manifest() else {
+        return;
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(state.day as u64));
+
+    // Sorted first, so the same seed shuffles them the same way.
+    let mut petitioners: Vec<&str> = characters
+        .table
+        .keys()
+        .copied()
+        .filter(|key| *key != "dream-man")
+        .collect();
+    petitioners.sort_unstable();
+    petitioners.shuffle(&mut rng);
+
+    // Each comes at most once, since the same character is never heard twice in a row and
+    // could otherwise be left waiting at the end of the day.
+    let mut requests: HashMap<&str, Vec<Request>> = HashMap::default();
+    for petitioner in petitioners.iter().take(manifest.petitioners(state.day)) {
+        let repeatable: Vec<&Request> = story_requests
+            .0
+            .get(petitioner)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|request| request.repeatable)
+            .collect();
+
+        let request = match repeatable.choose(&mut rng) {
+            Some(request) if !rng.gen_bool(manifest.generated_chance) => {
+                // Filters and handlers belong to the story.
+                Request {
+                    filter: None,
+                    response_handlers: Vec::new(),
+                    availability: Default::default(),
+                    ..(*request).clone()
+                }
+            }
+            _ => match manifest.generated.choose(&mut rng) {
+                Some(generated) => generated.request(manifest, &mut rng),
+                None => continue,
+            },
+        };
+
+        requests.entry(*petitioner).or_default().push(request);
+    }
+
+    for (key, handle) in characters.table.iter() {
+        let Some(character) = character_assets.get_mut(handle) else {
+            continue;
+        };
+
+        if character.requests.len() <= state.day {
+            character.requests.resize_with(state.day + 1, Vec::new);
+        }
+        character.requests[state.day] = requests.remove(*key).unwrap_or_default();
+    }
+}
+
+/// Each morning decisions weigh more and the heart's bounds close in, though never to
+/// within a step of where the heart is.
+fn raise_stakes(
+    mut state: ResMut<KingdomState>,
+    endless: Endless,
+    mut heart: EventWriter<NewHeartSize>,
+) {
+    let Some(manifest) = endless.manifest() else {
+        return;
+    };
+
+    let heart_size = state.heart_size;
+    let profile = &mut state.profile;
+    profile.delta_multiplier += manifest.delta_growth;
+
+    // A step is as far as a decision moves the heart.
+    let step = profile.delta_multiplier;
+    let min = (profile.min_heart_size + manifest.heart_narrowing).min(heart_size - step);
+    profile.min_heart_size = profile.min_heart_size.max(min);
+    let max = (profile.max_heart_size - manifest.heart_narrowing).max(heart_size + step);
+    profile.max_heart_size = profile.max_heart_size.min(max);
+
+    // The heart is drawn against its bounds.
+    heart.send(NewHeartSize(heart_size));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> EndlessManifest {
+        serde_yaml::from_str(include_str!("../assets/endless/court.endless.yaml")).unwrap()
+    }
+
+    #[test]
+    fn parses_the_court_manifest() {
+        let manifest = manifest();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!(!manifest.generated.is_empty());
+        for generated in manifest.generated.iter() {
+            let request = generated.request(&manifest, &mut rng);
+            assert!(!request.text.contains('{'), "unfilled: {}", request.text);
+        }
+    }
+
+    #[test]
+    fn adds_petitioners_up_to_the_most() {
+        let manifest = manifest();
+
+        assert_eq!(manifest.petitioners(0), 4);
+        assert_eq!(manifest.petitioners(2), 5);
+        assert_eq!(manifest.petitioners(1000), manifest.max_petitioners);
+    }
+
+    #[test]
+    fn keeps_placeholders_without_words() {
+        let manifest = manifest();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(manifest.fill("the {dragon}", &mut rng), "the {dragon}");
+        assert_eq!(manifest.fill("an open {brace", &mut rng), "an open {brace");
+    }
+
+    #[test]
+    fn samples_whole_numbers_within_range() {
+        let update = GeneratedUpdate {
+            wealth: [-12., -6.],
+            happiness: [4., 4.],
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let sample = update.sample(&mut rng);
+            assert!((-12. ..=-6.).contains(&sample.wealth));
+            assert_eq!(sample.wealth, sample.wealth.round());
+            assert_eq!(sample.happiness, 4.);
+            assert_eq!(sample.heart_size, 0.);
+        }
+    }
+}
@@ -1,8 +1,4 @@
-use bevy::{
-    input::{keyboard::KeyboardInput, ButtonState},
-    prelude::*,
-    window::{PrimaryWindow, WindowResized, WindowResolution},
-};
+use bevy::prelude::*;
 use bevy_asset_loader::loading_state::{
     config::ConfigureLoadingState, LoadingState, LoadingStateAppExt,
 };
@@ -10,33 +6,43 @@ use bevy_kira_audio::prelude::*;
 use character::{CharacterAssets, CharacterPlugin};
 use menu::MainMenuPlugin;
 use pixel_perfect::PixelPerfectPlugin;
+use serde::Deserialize;
+use settings::Settings;
 use state::{StatePlugin, StateUpdate};
 use ui::UiPlugin;
 
+mod accessibility;
+mod ambience;
 mod animated_sprites;
-mod animation;
 mod character;
+mod config;
+mod controls;
+mod cutscene;
+mod difficulty;
 mod end;
+mod endless;
 mod menu;
+mod mixer;
 mod music;
+mod particles;
 mod pixel_perfect;
+mod scenery;
+mod settings;
 mod state;
 mod time_state;
+mod transition;
 mod type_writer;
 mod ui;
+mod weather;
 
 fn main() {
+    let settings = config::load::<Settings>(Settings::FILE);
+
     App::new()
         .add_plugins((
             DefaultPlugins
                 .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "Concoeur".into(),
-                        resolution: WindowResolution::new(1920., 1080.)
-                            .with_scale_factor_override(1.0),
-                        mode: bevy::window::WindowMode::BorderlessFullscreen,
-                        ..Default::default()
-                    }),
+                    primary_window: Some(settings.window()),
                     ..Default::default()
                 })
                 .set(ImagePlugin::default_nearest()),
@@ -47,9 +53,23 @@ fn main() {
             MainMenuPlugin,
             AudioPlugin,
             music::MusicPlugin,
-            animation::AnimationPlugin,
             end::EndPlugin,
             time_state::TimeStatePlugin,
+            type_writer::TypeWriterPlugin,
+            controls::ControlsPlugin,
+            accessibility::AccessibilityPlugin,
+            (
+                settings::SettingsPlugin,
+                mixer::MixerPlugin,
+                ambience::AmbiencePlugin,
+                scenery::SceneryPlugin,
+                particles::ParticlesPlugin,
+                weather::WeatherPlugin,
+                transition::TransitionPlugin,
+                cutscene::CutscenePlugin,
+                difficulty::DifficultyPlugin,
+                endless::EndlessPlugin,
+            ),
         ))
         .configure_sets(Update, CharacterSet.run_if(in_state(GameState::Main)))
         .configure_sets(PreUpdate, CharacterSet.run_if(in_state(GameState::Main)))
@@ -58,16 +78,32 @@ fn main() {
         .add_loading_state(
             LoadingState::new(GameState::AssetLoading)
                 .continue_to_state(GameState::MainMenu)
-                .load_collection::<CharacterAssets>(),
+                .load_collection::<CharacterAssets>()
+                .load_collection::<music::MusicAssets>()
+                .load_collection::<ambience::AmbienceAssets>()
+                .load_collection::<scenery::SceneryAssets>()
+                .load_collection::<particles::ParticleAssets>()
+                .load_collection::<weather::WeatherAssets>()
+                .load_collection::<cutscene::CutsceneAssets>()
+                .load_collection::<difficulty::DifficultyAssets>()
+                .load_collection::<endless::EndlessAssets>(),
         )
         // .add_systems(Startup, menu::setup_cursor)
-        .add_systems(PreUpdate, update_window_scale_factor.before(CharacterSet))
         .insert_resource(ClearColor(Color::BLACK))
-        .add_systems(Update, (close_on_escape, animated_sprites::animate_sprites))
+        .insert_resource(settings)
+        .add_systems(
+            Update,
+            (
+                animated_sprites::update_sprite_animations,
+                animated_sprites::animate_sprites,
+            )
+                .chain(),
+        )
         .run();
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum GameState {
     #[default]
     AssetLoading,
@@ -84,29 +120,3 @@ pub struct SkipRemove;
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 struct CharacterSet;
-
-fn close_on_escape(mut input: EventReader<KeyboardInput>, mut writer: EventWriter<AppExit>) {
-    #[cfg(debug_assertions)]
-    for e in input.read() {
-        if matches!(e, KeyboardInput {
-            key_code,
-            state,
-            ..
-        }
-            if *key_code == KeyCode::Escape && *state == ButtonState::Pressed
-        ) {
-            writer.send(AppExit::Success);
-        }
-    }
-}
-
-fn update_window_scale_factor(
-    mut windows: Query<&mut Window, With<PrimaryWindow>>,
-    mut resize: EventReader<WindowResized>,
-) {
-    // for resize in resize.read() {
-    //     info!("{resize:?}");
-    //     let mut window = windows.single_mut();
-    //     window.resolution.set(1920., 1080.);
-    // }
-}
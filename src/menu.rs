@@ -1,7 +1,14 @@
 use crate::{
-    pixel_perfect::HIGH_RES_LAYER,
-    type_writer::{self, TypeWriter},
-    ui::{Cursor, InsightToolTip, UiNode, FONT_PATH},
+    accessibility::Accessibility,
+    controls::{Action, Controls},
+    difficulty::{Difficulties, Difficulty},
+    endless::Reign,
+    mixer::Bus,
+    particles::Particles,
+    pixel_perfect::{PixelCursor, RES_HEIGHT, RES_WIDTH, UI_DESIGN_SIZE},
+    state::DayStart,
+    type_writer::{self, TypeWriter, TypeWriterText},
+    ui::{settings::OpenSettingsScreen, Cursor, InsightToolTip, UiNode, FONT_PATH},
     GameState, SkipRemove,
 };
 use bevy::{
@@ -12,7 +19,7 @@ use bevy::{
     ui::ContentSize,
     window::PrimaryWindow,
 };
-use bevy_hanabi::prelude::*;
+use bevy_tweening::{lens::SpriteColorLens, Animator, EaseFunction, Tween};
 use sickle_ui::{prelude::*, ui_commands::UpdateStatesExt};
 use std::time::Duration;
 
@@ -20,22 +27,52 @@ pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(GameState::MainMenu),
-            #[cfg(target_arch = "wasm32")]
-            (setup, setup_cursor),
-            #[cfg(not(target_arch = "wasm32"))]
-            (setup_effect, setup, setup_cursor),
-        )
-        .add_systems(Update, parallax_sprites)
-        .add_systems(Update, (update_text,).run_if(in_state(GameState::MainMenu)))
-        .add_systems(Update, crate::ui::update_cursor);
-
-        #[cfg(not(target_arch = "wasm32"))]
-        app.add_plugins(HanabiPlugin);
+        app.add_sub_state::<MenuScreen>()
+            .insert_resource(TitleCursor::default())
+            .insert_resource(DifficultyCursor::default())
+            .add_systems(
+                OnEnter(GameState::MainMenu),
+                (setup_effect, setup, setup_cursor),
+            )
+            .add_systems(OnExit(GameState::MainMenu), despawn_menu)
+            .add_systems(OnEnter(MenuScreen::Title), open_title)
+            .add_systems(OnExit(MenuScreen::Title), close_title)
+            .add_systems(OnEnter(MenuScreen::Difficulty), open_difficulty)
+            .add_systems(OnExit(MenuScreen::Difficulty), close_difficulty)
+            .add_systems(OnEnter(MenuScreen::Credits), open_credits)
+            .add_systems(OnExit(MenuScreen::Credits), close_credits)
+            .add_systems(OnEnter(MenuScreen::Intro), setup_intro)
+            .add_systems(Update, parallax_sprites)
+            .add_systems(
+                Update,
+                (navigate_title, update_title_rows)
+                    .chain()
+                    .run_if(in_state(MenuScreen::Title)),
+            )
+            .add_systems(
+                Update,
+                (navigate_difficulty, update_difficulty_rows)
+                    .chain()
+                    .run_if(in_state(MenuScreen::Difficulty)),
+            )
+            .add_systems(Update, roll_credits.run_if(in_state(MenuScreen::Credits)))
+            .add_systems(Update, (update_text,).run_if(in_state(MenuScreen::Intro)))
+            .add_systems(Update, crate::ui::update_cursor);
     }
 }
 
+/// Where the player is in the main menu. New games, seasonal or endless, pick a difficulty,
+/// then play the intro couplet before the court.
+#[derive(SubStates, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[source(GameState = GameState::MainMenu)]
+enum MenuScreen {
+    #[default]
+    Title,
+    Difficulty,
+    Credits,
+    Intro,
+}
+
 #[derive(Component)]
 pub struct ParallaxSprite(pub f32);
 
@@ -115,13 +152,13 @@ pub fn setup_cursor(
 #[derive(Resource)]
 struct EnterMorningTimer(Timer, u32, bool);
 
+/// Everything the menu spawns over the game's own scenery, despawned when the game starts.
 #[derive(Component)]
 struct Intro;
 
 fn setup(
     mut commands: Commands,
     server: Res<AssetServer>,
-    mut type_writer: ResMut<TypeWriter>,
     mut cursor: Query<&mut Visibility, With<Cursor>>,
 ) {
     for mut vis in cursor.iter_mut() {
@@ -129,53 +166,18 @@ fn setup(
         *vis = Visibility::Visible;
     }
 
-    commands.insert_resource(EnterMorningTimer(
-        Timer::from_seconds(5., TimerMode::Repeating),
-        0,
-        false,
-    ));
-
     commands.spawn((
-        AudioBundle {
-            source: server.load("audio/birds-19624.mp3"),
-            settings: PlaybackSettings {
+        Bus::Ambience.sound(
+            server.load("audio/birds-19624.mp3"),
+            PlaybackSettings {
                 mode: PlaybackMode::Loop,
                 volume: Volume::new(0.5),
                 ..Default::default()
             },
-        },
+        ),
         Intro,
     ));
 
-    commands.spawn((
-        TextBundle::from_section(
-            "",
-            TextStyle {
-                font: server.load(FONT_PATH),
-                font_size: 49.,
-                ..default()
-            },
-        )
-        .with_text_justify(JustifyText::Left)
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            left: Val::Px(600.),
-            top: Val::Px(200.),
-            // max_width: Val::Px(1000.),
-            ..Default::default()
-        }),
-        IntroText,
-        Intro,
-    ));
-
-    let sfx = server.load("audio/cursor_style_2_rev.wav");
-    *type_writer = TypeWriter::new(
-        "Your heart, dear King, it weighs the will of one\nWho seeks of you a choice, a thing undone. "
-            .into(),
-        0.05,
-        sfx,
-    );
-
     commands.spawn((
         SpriteBundle {
             texture: server.load("Nature Landscapes Free Pixel Art/nature_4/1.png"),
@@ -210,114 +212,666 @@ fn setup(
     ));
 }
 
-fn parallax_sprites(
-    windows: Query<&Window>,
-    mut sprites: Query<(&mut Transform, &ParallaxSprite)>,
-) {
-    let window = windows.single();
+fn despawn_menu(mut commands: Commands, entities: Query<Entity, With<Intro>>) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
 
-    if let Some(world_position) = window.cursor_position() {
-        for (mut transform, parallax) in sprites.iter_mut() {
-            transform.translation.x = (world_position.x - 960.) * parallax.0;
-            transform.translation.y = (world_position.y - 540.) * parallax.0;
+const FONT_SIZE: f32 = 30.;
+const TEXT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FOCUS_COLOR: Color = Color::WHITE;
+const DISABLED_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// Design pixels per pixel of the menu art, half the size of the pixel canvas.
+const ART_SCALE: f32 = 4.;
+
+/// Part of a larger image, drawn on its own.
+struct Crop {
+    path: &'static str,
+    /// The size of the whole image.
+    size: UVec2,
+    rect: URect,
+}
+
+/// The banner in the logo.
+const LOGO: Crop = Crop {
+    path: "ui/logo.png",
+    size: UVec2::splat(128),
+    rect: URect {
+        min: UVec2::new(0, 46),
+        max: UVec2::new(128, 80),
+    },
+};
+
+/// The button in `main_menu_box.png`, which is drawn over the whole canvas.
+const MENU_BOX: Crop = Crop {
+    path: "ui/main_menu_box.png",
+    size: UVec2::new(RES_WIDTH, RES_HEIGHT),
+    rect: URect {
+        min: UVec2::new(85, 58),
+        max: UVec2::new(139, 78),
+    },
+};
+
+#[derive(Component)]
+struct TitleScreen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum TitleRow {
+    NewGame,
+    Endless,
+    Continue,
+    Settings,
+    Credits,
+    Quit,
+}
+
+const ROWS: [TitleRow; 6] = [
+    TitleRow::NewGame,
+    TitleRow::Endless,
+    TitleRow::Continue,
+    TitleRow::Settings,
+    TitleRow::Credits,
+    TitleRow::Quit,
+];
+
+impl TitleRow {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::NewGame => "New Game",
+            Self::Endless => "Endless Reign",
+            Self::Continue => "Continue",
+            Self::Settings => "Settings",
+            Self::Credits => "Credits",
+            Self::Quit => "Quit",
         }
     }
 }
 
+/// The label on a [`TitleRow`]'s button.
 #[derive(Component)]
-struct MainMenuParticles;
+struct TitleLabel(TitleRow);
 
-fn setup_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
-    #[cfg(target_arch = "wasm32")]
-    {
-        return;
+#[derive(Default, Resource)]
+struct TitleCursor {
+    row: usize,
+    /// Whether there is a saved day to continue from.
+    can_continue: bool,
+}
+
+impl TitleCursor {
+    fn is_enabled(&self, row: TitleRow) -> bool {
+        row != TitleRow::Continue || self.can_continue
     }
+}
 
-    commands.spawn((
-        Camera2dBundle {
-            camera: Camera {
-                order: 1,
-                clear_color: ClearColorConfig::None,
+/// An image of only the cropped part, at [`ART_SCALE`].
+fn cropped_image(
+    server: &AssetServer,
+    layouts: &mut Assets<TextureAtlasLayout>,
+    crop: &Crop,
+) -> (ImageBundle, TextureAtlas) {
+    let size = crop.rect.size().as_vec2() * ART_SCALE;
+
+    let mut layout = TextureAtlasLayout::new_empty(crop.size);
+    let index = layout.add_texture(crop.rect);
+
+    (
+        ImageBundle {
+            image: UiImage::new(server.load(crop.path)),
+            style: Style {
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
                 ..Default::default()
             },
             ..Default::default()
         },
-        RenderLayers::layer(2),
-    ));
+        TextureAtlas {
+            layout: layouts.add(layout),
+            index,
+        },
+    )
+}
+
+fn open_title(
+    mut commands: Commands,
+    mut cursor: ResMut<TitleCursor>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    server: Res<AssetServer>,
+) {
+    let can_continue = DayStart::load().is_some();
+    let focused = if can_continue {
+        TitleRow::Continue
+    } else {
+        TitleRow::NewGame
+    };
+    *cursor = TitleCursor {
+        row: ROWS
+            .iter()
+            .position(|row| *row == focused)
+            .unwrap_or_default(),
+        can_continue,
+    };
+
+    let style = TextStyle {
+        font_size: FONT_SIZE,
+        font: server.load(FONT_PATH),
+        color: TEXT_COLOR,
+    };
+
+    commands.ui_builder(UiRoot).container(
+        (
+            TitleScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ),
+        |title| {
+            title
+                .spawn(cropped_image(&server, &mut layouts, &LOGO))
+                .style()
+                .margin(UiRect::bottom(Val::Px(40.)));
+
+            for row in ROWS {
+                let button = cropped_image(&server, &mut layouts, &MENU_BOX);
+                title.container((row, Interaction::default(), button), |button| {
+                    button.spawn((
+                        TitleLabel(row),
+                        TextBundle::from_section(row.label(), style.clone()),
+                    ));
+                });
+            }
+        },
+    );
+}
 
-    // Define a color gradient from red to transparent black
-    let mut gradient = Gradient::new();
-    gradient.add_key(0.0, Vec4::new(0., 0.8, 0.2, 1.));
-    gradient.add_key(1.0, Vec4::splat(0.));
+fn close_title(mut commands: Commands, screen: Query<Entity, With<TitleScreen>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
 
-    // Create a new expression module
-    let mut module = Module::default();
+fn navigate_title(
+    mut commands: Commands,
+    mut cursor: ResMut<TitleCursor>,
+    controls: Controls,
+    rows: Query<(&TitleRow, &Interaction), Changed<Interaction>>,
+    mut open_settings: EventWriter<OpenSettingsScreen>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // The settings screen is open over the menu.
+    if controls.is_locked() {
+        return;
+    }
 
-    // On spawn, randomly initialize the position of the particle
-    // to be over the surface of a sphere of radius 2 units.
-    let init_pos = SetPositionSphereModifier {
-        center: module.lit(Vec3::ZERO.with_z(200.)),
-        radius: module.lit(800.),
-        dimension: ShapeDimension::Surface,
+    let last = ROWS.len() - 1;
+    let mut activate = controls.menu_just_pressed(Action::Advance);
+
+    if controls.menu_just_pressed(Action::Up) {
+        cursor.row = cursor.row.checked_sub(1).unwrap_or(last);
+    } else if controls.menu_just_pressed(Action::Down) {
+        cursor.row = if cursor.row >= last {
+            0
+        } else {
+            cursor.row + 1
+        };
+    }
+
+    for (row, interaction) in rows.iter() {
+        let index = ROWS.iter().position(|r| r == row).unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => cursor.row = index,
+            Interaction::Pressed => {
+                cursor.row = index;
+                activate = true;
+            }
+            Interaction::None => {}
+        }
+    }
+
+    let row = ROWS[cursor.row];
+    if !activate || !cursor.is_enabled(row) {
+        return;
+    }
+
+    match row {
+        TitleRow::NewGame => {
+            commands.insert_resource(Reign::Seasons);
+            commands.next_state(MenuScreen::Difficulty);
+        }
+        TitleRow::Endless => {
+            commands.insert_resource(Reign::Endless {
+                seed: rand::random(),
+            });
+            commands.next_state(MenuScreen::Difficulty);
+        }
+        TitleRow::Continue => {
+            let Some(day_start) = DayStart::load() else {
+                cursor.can_continue = false;
+                return;
+            };
+            commands.insert_resource(day_start);
+            commands.next_state(GameState::Main);
+        }
+        TitleRow::Settings => {
+            open_settings.send(OpenSettingsScreen);
+        }
+        TitleRow::Credits => {
+            commands.next_state(MenuScreen::Credits);
+        }
+        TitleRow::Quit => {
+            exit.send(AppExit::Success);
+        }
+    }
+}
+
+fn update_title_rows(
+    cursor: Res<TitleCursor>,
+    mut buttons: Query<(&TitleRow, &mut UiImage)>,
+    mut labels: Query<(&TitleLabel, &mut Text)>,
+    added: Query<(), Added<TitleRow>>,
+) {
+    if !cursor.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let focused = ROWS[cursor.row];
+    for (row, mut image) in buttons.iter_mut() {
+        let shown = *row == focused && cursor.is_enabled(*row);
+        image.color.set_alpha(if shown { 1. } else { 0. });
+    }
+
+    for (label, mut text) in labels.iter_mut() {
+        text.sections[0].style.color = if !cursor.is_enabled(label.0) {
+            DISABLED_COLOR
+        } else if label.0 == focused {
+            FOCUS_COLOR
+        } else {
+            TEXT_COLOR
+        };
+    }
+}
+
+#[derive(Component)]
+struct DifficultyScreen;
+
+/// A difficulty's button.
+#[derive(Component)]
+struct DifficultyRow(Difficulty);
+
+/// The label on a [`DifficultyRow`]'s button.
+#[derive(Component)]
+struct DifficultyLabel(Difficulty);
+
+/// The focused difficulty's description.
+#[derive(Component)]
+struct DifficultyDescription;
+
+#[derive(Resource)]
+struct DifficultyCursor {
+    row: usize,
+}
+
+impl Default for DifficultyCursor {
+    fn default() -> Self {
+        let row = Difficulty::ALL
+            .iter()
+            .position(|difficulty| *difficulty == Difficulty::default())
+            .unwrap_or_default();
+
+        Self { row }
+    }
+}
+
+fn open_difficulty(
+    mut commands: Commands,
+    mut cursor: ResMut<DifficultyCursor>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    server: Res<AssetServer>,
+) {
+    *cursor = DifficultyCursor::default();
+
+    let style = TextStyle {
+        font_size: FONT_SIZE,
+        font: server.load(FONT_PATH),
+        color: TEXT_COLOR,
     };
 
-    // Also initialize a radial initial velocity to 6 units/sec
-    // away from the (same) sphere center.
-    let init_vel = SetVelocitySphereModifier {
-        center: module.lit(Vec3::new(-200., -200., 0.)),
-        speed: module.lit(20.),
+    commands.ui_builder(UiRoot).container(
+        (
+            DifficultyScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ),
+        |screen| {
+            screen
+                .spawn(TextBundle::from_section(
+                    "Difficulty",
+                    TextStyle {
+                        color: FOCUS_COLOR,
+                        ..style.clone()
+                    },
+                ))
+                .style()
+                .margin(UiRect::bottom(Val::Px(40.)));
+
+            for difficulty in Difficulty::ALL {
+                let button = cropped_image(&server, &mut layouts, &MENU_BOX);
+                screen.container(
+                    (DifficultyRow(difficulty), Interaction::default(), button),
+                    |button| {
+                        button.spawn((
+                            DifficultyLabel(difficulty),
+                            TextBundle::from_section(difficulty.label(), style.clone()),
+                        ));
+                    },
+                );
+            }
+
+            screen
+                .spawn((
+                    DifficultyDescription,
+                    TextBundle::from_section("", style.clone())
+                        .with_text_justify(JustifyText::Center),
+                ))
+                .style()
+                .max_width(Val::Px(1000.))
+                .margin(UiRect::top(Val::Px(40.)));
+        },
+    );
+}
+
+fn close_difficulty(mut commands: Commands, screen: Query<Entity, With<DifficultyScreen>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn navigate_difficulty(
+    mut commands: Commands,
+    mut cursor: ResMut<DifficultyCursor>,
+    controls: Controls,
+    rows: Query<(&DifficultyRow, &Interaction), Changed<Interaction>>,
+) {
+    if controls.is_locked() {
+        return;
+    }
+
+    if controls.menu_just_pressed(Action::Pause) {
+        commands.next_state(MenuScreen::Title);
+        return;
+    }
+
+    let last = Difficulty::ALL.len() - 1;
+    let mut activate = controls.menu_just_pressed(Action::Advance);
+
+    if controls.menu_just_pressed(Action::Up) {
+        cursor.row = cursor.row.checked_sub(1).unwrap_or(last);
+    } else if controls.menu_just_pressed(Action::Down) {
+        cursor.row = if cursor.row >= last {
+            0
+        } else {
+            cursor.row + 1
+        };
+    }
+
+    for (row, interaction) in rows.iter() {
+        let index = Difficulty::ALL
+            .iter()
+            .position(|difficulty| *difficulty == row.0)
+            .unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => cursor.row = index,
+            Interaction::Pressed => {
+                cursor.row = index;
+                activate = true;
+            }
+            Interaction::None => {}
+        }
+    }
+
+    if !activate {
+        return;
+    }
+
+    // A new reign replaces the saved one.
+    commands.remove_resource::<DayStart>();
+    DayStart::remove();
+    commands.insert_resource(Difficulty::ALL[cursor.row]);
+    commands.next_state(MenuScreen::Intro);
+}
+
+fn update_difficulty_rows(
+    cursor: Res<DifficultyCursor>,
+    difficulties: Difficulties,
+    mut buttons: Query<(&DifficultyRow, &mut UiImage)>,
+    mut labels: Query<(&DifficultyLabel, &mut Text), Without<DifficultyDescription>>,
+    mut description: Query<&mut Text, With<DifficultyDescription>>,
+    added: Query<(), Added<DifficultyRow>>,
+) {
+    if !cursor.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let focused = Difficulty::ALL[cursor.row];
+    for (row, mut image) in buttons.iter_mut() {
+        image
+            .color
+            .set_alpha(if row.0 == focused { 1. } else { 0. });
+    }
+
+    for (label, mut text) in labels.iter_mut() {
+        text.sections[0].style.color = if label.0 == focused {
+            FOCUS_COLOR
+        } else {
+            TEXT_COLOR
+        };
+    }
+
+    if let (Ok(mut text), Some(profile)) =
+        (description.get_single_mut(), difficulties.profile(focused))
+    {
+        text.sections[0].value.clone_from(&profile.description);
+    }
+}
+
+const CREDITS: &str = "Concoeur\n\n\
+    Made for the GMTK Game Jam 2024\n\n\
+    UI art by Finnmercury\n\
+    Landscapes by CraftPix.net\n\n\
+    Thank you for playing";
+
+/// How fast the credits roll, in percent of the screen per second.
+const CREDITS_SPEED: f32 = 8.;
+
+#[derive(Component)]
+struct Credits;
+
+fn open_credits(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    accessibility: Res<Accessibility>,
+) {
+    // With reduced motion the credits hold still until a press.
+    let top = if accessibility.reduced_motion {
+        30.
+    } else {
+        100.
     };
 
-    let init_size = SetSizeModifier {
-        size: CpuValue::Uniform((Vec2::splat(4.), Vec2::splat(16.))),
+    commands.spawn((
+        Credits,
+        TextBundle::from_section(
+            CREDITS,
+            TextStyle {
+                font: server.load(FONT_PATH),
+                font_size: 40.,
+                color: FOCUS_COLOR,
+            },
+        )
+        .with_text_justify(JustifyText::Center)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            top: Val::Percent(top),
+            ..Default::default()
+        }),
+    ));
+}
+
+fn roll_credits(
+    mut commands: Commands,
+    controls: Controls,
+    mut credits: Query<&mut Style, With<Credits>>,
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+) {
+    let Ok(mut style) = credits.get_single_mut() else {
+        return;
+    };
+    let Val::Percent(top) = style.top else {
+        return;
     };
 
-    // Initialize the total lifetime of the particle, that is
-    // the time for which it's simulated and rendered. This modifier
-    // is almost always required, otherwise the particles won't show.
-    let lifetime = module.lit(10.); // literal value "10.0"
-    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, lifetime);
-
-    // Every frame, add a gravity-like acceleration downward
-    let accel = module.lit(Vec3::new(0., 0., 0.));
-    let update_accel = AccelModifier::new(accel);
-
-    // Create the effect asset
-    let effect = EffectAsset::new(
-        // Maximum number of particles alive at a time
-        vec![100],
-        // Spawn at a rate of 5 particles per second
-        Spawner::rate(5.0.into()),
-        // Move the expression module into the asset
-        module,
-    )
-    .with_simulation_space(SimulationSpace::Local)
-    .with_name("MyEffect")
-    .init(init_pos)
-    .init(init_vel)
-    .init(init_lifetime)
-    .update(update_accel)
-    .render(init_size)
-    // Render the particles with a color gradient over their
-    // lifetime. This maps the gradient key 0 to the particle spawn
-    // time, and the gradient key 1 to the particle death (10s).
-    .render(ColorOverLifetimeModifier { gradient });
-
-    // Insert into the asset system
-    let effect_asset = effects.add(effect);
+    if controls.any() || top < -50. {
+        commands.next_state(MenuScreen::Title);
+    } else if !accessibility.reduced_motion {
+        style.top = Val::Percent(top - CREDITS_SPEED * time.delta_seconds());
+    }
+}
+
+fn close_credits(mut commands: Commands, credits: Query<Entity, With<Credits>>) {
+    for entity in credits.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn setup_intro(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    mut type_writer: ResMut<TypeWriter>,
+) {
+    commands.insert_resource(EnterMorningTimer(
+        Timer::from_seconds(5., TimerMode::Repeating),
+        0,
+        false,
+    ));
 
+    // The title card fades while the first line waits to be written.
     commands.spawn((
-        ParticleEffectBundle {
-            effect: ParticleEffect::new(effect_asset),
-            transform: Transform::from_translation(Vec3::default().with_z(300.))
-                .with_scale(Vec3::splat(1.)),
+        SpriteBundle {
+            texture: server.load("ui/new_game_text.png"),
+            transform: Transform::from_xyz(0., 0., 10.),
             ..Default::default()
         },
-        RenderLayers::layer(2),
-        MainMenuParticles,
+        Animator::new(Tween::new(
+            EaseFunction::QuadraticIn,
+            Duration::from_secs_f32(4.),
+            SpriteColorLens {
+                start: Color::WHITE,
+                end: Color::WHITE.with_alpha(0.),
+            },
+        )),
         Intro,
-        ParallaxSprite(0.045),
     ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: server.load(FONT_PATH),
+                font_size: 49.,
+                ..default()
+            },
+        )
+        .with_text_justify(JustifyText::Left)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(600.),
+            top: Val::Px(200.),
+            // max_width: Val::Px(1000.),
+            ..Default::default()
+        }),
+        IntroText,
+        TypeWriterText::default(),
+        Intro,
+    ));
+
+    let sfx = server.load("audio/cursor_style_2_rev.wav");
+    *type_writer = TypeWriter::new(
+        "Your heart, dear King, it weighs the will of one\nWho seeks of you a choice, a thing undone. "
+            .into(),
+        0.05,
+        sfx,
+    );
+}
+
+fn parallax_sprites(
+    pointer: PixelCursor,
+    mut sprites: Query<(&mut Transform, &ParallaxSprite)>,
+    accessibility: Res<Accessibility>,
+) {
+    if accessibility.reduced_motion {
+        for (mut transform, _) in sprites.iter_mut() {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+        }
+    } else if let Some(fraction) = pointer.canvas_fraction() {
+        let offset = (fraction - 0.5) * UI_DESIGN_SIZE;
+        for (mut transform, parallax) in sprites.iter_mut() {
+            transform.translation.x = offset.x * parallax.0;
+            transform.translation.y = offset.y * parallax.0;
+        }
+    }
+}
+
+#[derive(Component)]
+struct MainMenuParticles;
+
+fn setup_effect(mut commands: Commands, mut particles: Particles) {
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        RenderLayers::layer(2),
+    ));
+
+    let transform = Transform::from_translation(Vec3::default().with_z(300.));
+    if let Some(mut entity) = particles.spawn(&mut commands, "menu-motes", transform) {
+        entity.insert((
+            RenderLayers::layer(2),
+            MainMenuParticles,
+            Intro,
+            ParallaxSprite(0.045),
+        ));
+    }
 }
 
 #[derive(Component)]
@@ -325,12 +879,12 @@ struct IntroText;
 
 fn update_text(
     mut commands: Commands,
-    mut intro_text: Query<&mut Text, With<IntroText>>,
+    mut intro_text: Query<(&mut Text, &mut TypeWriterText), With<IntroText>>,
     mut type_writer: ResMut<TypeWriter>,
     mut reader: EventReader<KeyboardInput>,
+    controls: Controls,
     time: Res<Time>,
     mut timer: ResMut<EnterMorningTimer>,
-    enitites: Query<Entity, With<Intro>>,
     server: Res<AssetServer>,
 ) {
     if !timer.2 {
@@ -338,36 +892,26 @@ fn update_text(
         timer.2 = true;
     }
 
-    for input in reader.read() {
-        if matches!(
-            input,
-            KeyboardInput {
-                state,
-                ..
-            } if *state
-                == ButtonState::Pressed
-        ) {
-            if !type_writer.is_finished {
-                type_writer.finish();
-                continue;
-            }
+    let pressed = reader
+        .read()
+        .any(|input| input.state == ButtonState::Pressed && !controls.is_locked())
+        || controls.any();
 
-            timer.1 += 1;
+    if pressed && !type_writer.is_finished {
+        type_writer.finish();
+    } else if pressed {
+        timer.1 += 1;
 
-            if timer.1 == 3 {
-                let sfx = server.load("audio/cursor_style_2_rev.wav");
-                let line = "Closely must You watch this beating sieve;\nToo much, too little, and Your heart will give.";
-                *type_writer = TypeWriter::new(line.into(), 0.035, sfx);
-                timer.0.set_duration(Duration::from_secs_f32(7.));
-            }
+        if timer.1 == 3 {
+            let sfx = server.load("audio/cursor_style_2_rev.wav");
+            let line = "Closely must You watch this beating sieve;\nToo much, too little, and Your heart will give.";
+            *type_writer = TypeWriter::new(line.into(), 0.035, sfx);
+            timer.0.set_duration(Duration::from_secs_f32(7.));
+        }
 
-            if timer.1 == 5 {
-                commands.next_state(GameState::Main);
-                for entity in enitites.iter() {
-                    commands.entity(entity).despawn();
-                }
-                return;
-            }
+        if timer.1 == 5 {
+            commands.next_state(GameState::Main);
+            return;
         }
     }
 
@@ -385,9 +929,6 @@ fn update_text(
 
         if timer.1 == 5 {
             commands.next_state(GameState::Main);
-            for entity in enitites.iter() {
-                commands.entity(entity).despawn();
-            }
             return;
         }
     }
@@ -396,7 +937,7 @@ fn update_text(
         type_writer.increment(&time);
         type_writer.try_play_sound(&mut commands);
 
-        let mut text = intro_text.single_mut();
-        text.sections[0].value = type_writer.slice_with_line_wrap().into();
+        let (mut text, mut writer_text) = intro_text.single_mut();
+        type_writer.write_sections(&mut text, &mut writer_text);
     }
 }
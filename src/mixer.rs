@@ -0,0 +1,242 @@
+//! One mixer for every sound in the game, whether it plays through bevy audio or kira.
+//!
+//! Bevy audio sounds are spawned with [`Bus::sound`], which tags them with their bus.
+//! Music plays through kira and reads its volume from [`Mixer::music_volume`].
+
+use crate::{controls::ControlsLock, music::MUSIC_VOL, settings::Settings};
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Mixer::default())
+            .add_systems(
+                Update,
+                (sync_settings, duck_under_overlays, tick_fades).chain(),
+            )
+            .add_systems(PostUpdate, (apply_sink_volumes, hold_sinks));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Serialize, Deserialize)]
+pub enum Bus {
+    Music,
+    Sfx,
+    /// Character voices, such as the typewriter blips.
+    Voice,
+    /// Looping background sounds.
+    Ambience,
+}
+
+impl Bus {
+    pub const ALL: [Bus; 4] = [Bus::Music, Bus::Sfx, Bus::Voice, Bus::Ambience];
+
+    /// A bevy audio sound routed through this bus.
+    pub fn sound(
+        self,
+        source: Handle<AudioSource>,
+        settings: PlaybackSettings,
+    ) -> (AudioBundle, Bus) {
+        (AudioBundle { source, settings }, self)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Fade {
+    from: f32,
+    to: f32,
+    timer: Timer,
+}
+
+#[derive(Debug, Clone)]
+struct BusState {
+    /// The player's volume for this bus.
+    volume: f32,
+    /// The game's level for this bus, which fades.
+    level: f32,
+    fade: Option<Fade>,
+    muted: bool,
+}
+
+impl Default for BusState {
+    fn default() -> Self {
+        Self {
+            volume: 1.,
+            level: 1.,
+            fade: None,
+            muted: false,
+        }
+    }
+}
+
+#[derive(Debug, Resource)]
+pub struct Mixer {
+    master: f32,
+    buses: HashMap<Bus, BusState>,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            master: 1.,
+            buses: Bus::ALL
+                .into_iter()
+                .map(|bus| (bus, BusState::default()))
+                .collect(),
+        }
+    }
+}
+
+impl Mixer {
+    fn bus(&self, bus: Bus) -> &BusState {
+        &self.buses[&bus]
+    }
+
+    fn bus_mut(&mut self, bus: Bus) -> &mut BusState {
+        self.buses.entry(bus).or_default()
+    }
+
+    /// Everything applied on top of a sound's own volume.
+    pub fn gain(&self, bus: Bus) -> f32 {
+        let state = self.bus(bus);
+        if state.muted {
+            0.
+        } else {
+            self.master * state.volume * state.level
+        }
+    }
+
+    /// Volume for music played through kira.
+    pub fn music_volume(&self) -> f64 {
+        MUSIC_VOL * self.gain(Bus::Music) as f64
+    }
+
+    pub fn set_muted(&mut self, bus: Bus, muted: bool) {
+        self.bus_mut(bus).muted = muted;
+    }
+
+    /// Fades the level of `bus` to `level` over `secs`, starting from wherever it is now.
+    pub fn fade_to(&mut self, bus: Bus, level: f32, secs: f32) {
+        let state = self.bus_mut(bus);
+        if secs <= 0. {
+            state.level = level;
+            state.fade = None;
+            return;
+        }
+
+        state.fade = Some(Fade {
+            from: state.level,
+            to: level,
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+        });
+    }
+}
+
+fn sync_settings(settings: Res<Settings>, mut mixer: ResMut<Mixer>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    mixer.master = settings.master_volume;
+    mixer.bus_mut(Bus::Music).volume = settings.music_volume;
+    mixer.bus_mut(Bus::Sfx).volume = settings.sfx_volume;
+    mixer.bus_mut(Bus::Voice).volume = settings.voice_volume;
+    mixer.bus_mut(Bus::Ambience).volume = settings.ambience_volume;
+    for bus in Bus::ALL {
+        mixer.set_muted(bus, settings.is_muted(bus));
+    }
+}
+
+/// Music and ambience sit lower while a menu is open over the game.
+fn duck_under_overlays(lock: Res<ControlsLock>, mut mixer: ResMut<Mixer>) {
+    if !lock.is_changed() {
+        return;
+    }
+
+    let level = if lock.0 { 0.4 } else { 1. };
+    mixer.fade_to(Bus::Music, level, 0.3);
+    mixer.fade_to(Bus::Ambience, level, 0.3);
+}
+
+fn tick_fades(mut mixer: ResMut<Mixer>, time: Res<Time>) {
+    if mixer.buses.values().all(|state| state.fade.is_none()) {
+        return;
+    }
+
+    for state in mixer.buses.values_mut() {
+        let Some(fade) = state.fade.as_mut() else {
+            continue;
+        };
+
+        fade.timer.tick(time.delta());
+        let t = fade.timer.fraction();
+        state.level = fade.from + (fade.to - fade.from) * t * t * (3. - 2. * t);
+
+        if fade.timer.finished() {
+            state.fade = None;
+        }
+    }
+}
+
+/// The volume a sink was given by the rest of the game, and the gain applied on top.
+#[derive(Component)]
+struct MixedVolume {
+    base: f32,
+    gain: f32,
+}
+
+/// Scales every bevy audio sink by the gain of its bus, or the sfx bus if it has none. A
+/// sink whose volume is no longer `base * gain` was set since the last pass, by a tween or
+/// otherwise, so that volume becomes its new base.
+fn apply_sink_volumes(
+    mut commands: Commands,
+    mixer: Res<Mixer>,
+    mut sinks: Query<(Entity, &AudioSink, Option<&Bus>, Option<&mut MixedVolume>)>,
+) {
+    for (entity, sink, bus, mixed) in sinks.iter_mut() {
+        let gain = mixer.gain(bus.copied().unwrap_or(Bus::Sfx));
+
+        let Some(mut mixed) = mixed else {
+            let base = sink.volume();
+            sink.set_volume(base * gain);
+            commands.entity(entity).insert(MixedVolume { base, gain });
+
+            continue;
+        };
+
+        let volume = sink.volume();
+        if volume != mixed.base * mixed.gain {
+            mixed.base = volume;
+        } else if gain == mixed.gain {
+            continue;
+        }
+
+        sink.set_volume(mixed.base * gain);
+        mixed.gain = gain;
+    }
+}
+
+/// A sink paused by [`hold_sinks`], which plays again once virtual time does.
+#[derive(Component)]
+struct Held;
+
+/// Pauses every playing bevy audio sink for as long as virtual time is paused.
+fn hold_sinks(
+    mut commands: Commands,
+    time: Res<Time<Virtual>>,
+    sinks: Query<(Entity, &AudioSink, Has<Held>)>,
+) {
+    let paused = time.is_paused();
+
+    for (entity, sink, held) in sinks.iter() {
+        if paused && !held && !sink.is_paused() {
+            sink.pause();
+            commands.entity(entity).insert(Held);
+        } else if !paused && held {
+            sink.play();
+            commands.entity(entity).remove::<Held>();
+        }
+    }
+}
@@ -1,68 +1,251 @@
-use crate::{time_state::TimeState, GameState};
-use bevy::{
-    input::{keyboard::KeyboardInput, ButtonState},
-    prelude::*,
+use crate::{
+    controls::{Action, Controls, DebugAction},
+    mixer::{Bus, Mixer},
+    state::{KingdomState, NewHeartSize},
+    time_state::TimeState,
+    GameState,
 };
+use bevy::{prelude::*, utils::HashMap};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
 use bevy_kira_audio::prelude::*;
+use serde::Deserialize;
+use std::borrow::Cow;
 
 pub struct MusicPlugin;
 
 impl Plugin for MusicPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Music::default())
+        app.add_plugins(YamlAssetPlugin::<MusicManifest>::new(&["music.yaml"]))
+            .insert_resource(Music::default())
             .add_event::<MusicEvent>()
-            .add_systems(Update, (handle_music_playback, test_music));
+            .add_systems(
+                Update,
+                (handle_music_playback, follow_heart_size, test_music),
+            )
+            .add_systems(
+                PostUpdate,
+                (hold_music, apply_music_volume, play_heartbeat).chain(),
+            );
     }
 }
 
-const MUSIC_VOL: f64 = 0.333;
+/// Volume of every music track, before the player's settings.
+pub const MUSIC_VOL: f64 = 0.333;
 
 pub fn play_final_stinger(commands: &mut Commands, assets: &AssetServer) {
-    commands.spawn(AudioBundle {
-        source: assets.load("audio/game-complete.wav"),
-        settings: PlaybackSettings {
-            mode: bevy::audio::PlaybackMode::Despawn,
-            ..Default::default()
-        }
-        .with_volume(bevy::audio::Volume::new(MUSIC_VOL as f32 * 0.75)),
-    });
+    commands.spawn(
+        Bus::Music.sound(
+            assets.load("audio/game-complete.wav"),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                ..Default::default()
+            }
+            .with_volume(bevy::audio::Volume::new(MUSIC_VOL as f32 * 0.75)),
+        ),
+    );
 }
 
 pub fn play_special_stinger(commands: &mut Commands, assets: &AssetServer) {
-    commands.spawn(AudioBundle {
-        source: assets.load("audio/special-stinger.wav"),
-        settings: PlaybackSettings {
-            mode: bevy::audio::PlaybackMode::Despawn,
-            ..Default::default()
+    commands.spawn(
+        Bus::Music.sound(
+            assets.load("audio/special-stinger.wav"),
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                ..Default::default()
+            }
+            .with_volume(bevy::audio::Volume::new(MUSIC_VOL as f32 * 0.75)),
+        ),
+    );
+}
+
+/// Every music track, keyed by [`MusicKind`], and the heartbeat layered over them.
+///
+/// ```yaml
+/// heartbeat:
+///   path: audio/heartbeat.wav
+///   bpm: 60
+/// tracks:
+///   day:
+///     path: audio/court-day.wav
+///     loop: { start: 6.15, end: 87.592 }
+///     bpm: 120
+///     volume: 1.0
+///     stems:
+///       - { path: audio/court-day-percussion.wav, layer: percussion }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct MusicManifest {
+    #[serde(default)]
+    pub heartbeat: Option<Heartbeat>,
+    pub tracks: HashMap<MusicKind, MusicTrack>,
+}
+
+/// A looping heartbeat that fades in and speeds up as the heart nears either bound.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Heartbeat {
+    pub path: String,
+    /// The tempo of the recording at its normal playback rate.
+    pub bpm: f64,
+    #[serde(default = "volume_default")]
+    pub volume: f64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MusicTrack {
+    pub path: String,
+    /// The region that repeats once playback reaches its end. Tracks without one play once.
+    #[serde(default, rename = "loop")]
+    pub loop_region: Option<LoopRegion>,
+    #[serde(default)]
+    pub bpm: Option<f64>,
+    /// Volume of the track before the mixer.
+    #[serde(default = "volume_default")]
+    pub volume: f64,
+    /// Extra parts that play in time with the track and fade in with the heart's tension.
+    #[serde(default)]
+    pub stems: Vec<Stem>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Stem {
+    pub path: String,
+    pub layer: StemLayer,
+    #[serde(default = "volume_default")]
+    pub volume: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StemLayer {
+    /// Comes in as soon as the heart leaves the middle.
+    Percussion,
+    /// Comes in once the heart is closer to a bound than to the middle.
+    Dissonance,
+}
+
+impl StemLayer {
+    fn level(self, tension: f64) -> f64 {
+        match self {
+            Self::Percussion => tension,
+            Self::Dissonance => ((tension - 0.5) * 2.).clamp(0., 1.),
         }
-        .with_volume(bevy::audio::Volume::new(MUSIC_VOL as f32 * 0.75)),
-    });
+    }
+}
+
+fn volume_default() -> f64 {
+    1.
+}
+
+/// Seconds into the track. Without an end, the loop runs to the end of the track.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct LoopRegion {
+    #[serde(default)]
+    pub start: f64,
+    #[serde(default)]
+    pub end: Option<f64>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct MusicAssets {
+    #[asset(path = "audio/court.music.yaml")]
+    manifest: Handle<MusicManifest>,
 }
 
-#[derive(Resource, Reflect, Default)]
+#[derive(Resource, Default)]
 struct Music {
-    playing: bool,
-    position: f64,
+    /// Every instance still audible, at most one of which is fading in or playing.
+    instances: Vec<MusicInstance>,
+    /// Where a paused track picks up if it is played again.
+    resume: Option<(MusicKind, f64)>,
+    /// How close the heart is to either bound, from 0 in the middle to 1 at a bound. Eases
+    /// towards `target_tension`.
+    tension: f64,
+    target_tension: f64,
+    heartbeat: Option<HeartbeatInstance>,
+}
+
+/// A playing track and how far it is through its fade.
+struct MusicInstance {
     kind: MusicKind,
+    handle: Handle<AudioInstance>,
+    /// The track's manifest volume.
+    volume: f64,
+    /// From silent at 0 to full volume at 1, along an equal-power curve.
+    phase: f64,
+    /// Phase per second. Negative while fading out, after which the instance stops.
+    rate: f64,
+    applied: Option<f64>,
+    /// Bpm from the manifest, which the heartbeat keeps time with.
+    bpm: Option<f64>,
+    stems: Vec<StemInstance>,
+}
+
+struct StemInstance {
+    layer: StemLayer,
+    handle: Handle<AudioInstance>,
+    volume: f64,
+    applied: Option<f64>,
 }
 
-#[derive(Debug, PartialEq, Reflect, Default)]
-pub enum MusicKind {
-    #[default]
-    Day,
-    Dream,
+struct HeartbeatInstance {
+    handle: Handle<AudioInstance>,
+    applied: Option<(f64, f64)>,
+}
+
+impl MusicInstance {
+    fn gain(&self) -> f64 {
+        equal_power(self.phase)
+    }
+}
+
+/// Gain at `phase` through a fade. A track fading in and one fading out at the same rate
+/// always add up to the same power, so a crossfade does not dip in the middle.
+fn equal_power(phase: f64) -> f64 {
+    (phase * std::f64::consts::FRAC_PI_2).sin()
+}
+
+impl Music {
+    fn current(&self) -> Option<&MusicInstance> {
+        self.instances.iter().find(|instance| instance.rate > 0.)
+    }
+
+    /// Fades every instance out over `secs`.
+    fn fade_out(&mut self, secs: f32) {
+        let rate = fade_rate(secs);
+        for instance in self.instances.iter_mut() {
+            instance.rate = -rate;
+        }
+    }
+}
+
+fn fade_rate(secs: f32) -> f64 {
+    1. / secs.max(0.01) as f64
+}
+
+/// The id of a track in the [`MusicManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct MusicKind(Cow<'static, str>);
+
+impl MusicKind {
+    pub const DAY: Self = Self(Cow::Borrowed("day"));
+    pub const DREAM: Self = Self(Cow::Borrowed("dream"));
 }
 
 #[derive(Debug, Event, PartialEq)]
 pub enum MusicEvent {
+    /// Crossfades to a track over a moment.
     Play(MusicKind),
     Pause,
     FadeOutSecs(f32),
-    FadeInSecs(MusicKind, f32),
+    /// Fades out whatever is playing while the track fades in, both over the given seconds.
+    /// A track that is already fading out fades back in from where it is.
+    CrossfadeTo(MusicKind, f32),
 }
 
 fn test_music(
-    mut key: EventReader<KeyboardInput>,
+    controls: Controls,
     mut event_writer: EventWriter<MusicEvent>,
     // mut end_day: EventWriter<EndDay>,
     mut time_state: ResMut<NextState<TimeState>>,
@@ -71,34 +254,30 @@ fn test_music(
 ) {
     #[cfg(debug_assertions)]
     {
-        for event in key.read() {
-            if event.state == ButtonState::Released {
-                continue;
-            }
+        let debug = |action| controls.just_pressed(Action::Debug(action));
 
-            if event.key_code == KeyCode::KeyJ {
-                event_writer.send(MusicEvent::Play(MusicKind::Dream));
-            }
+        if debug(DebugAction::PlayDream) {
+            event_writer.send(MusicEvent::Play(MusicKind::DREAM));
+        }
 
-            if event.key_code == KeyCode::KeyK {
-                event_writer.send(MusicEvent::Pause);
-            }
+        if debug(DebugAction::PauseMusic) {
+            event_writer.send(MusicEvent::Pause);
+        }
 
-            if event.key_code == KeyCode::KeyL {
-                event_writer.send(MusicEvent::FadeOutSecs(5.));
-            }
+        if debug(DebugAction::FadeOutMusic) {
+            event_writer.send(MusicEvent::FadeOutSecs(5.));
+        }
 
-            if event.key_code == KeyCode::Semicolon {
-                event_writer.send(MusicEvent::Play(MusicKind::Day));
-            }
+        if debug(DebugAction::PlayDay) {
+            event_writer.send(MusicEvent::Play(MusicKind::DAY));
+        }
 
-            if event.key_code == KeyCode::Quote {
-                time_state.set(TimeState::Evening);
-            }
+        if debug(DebugAction::Evening) {
+            time_state.set(TimeState::Evening);
+        }
 
-            if event.key_code == KeyCode::Comma {
-                play_final_stinger(&mut commands, &server);
-            }
+        if debug(DebugAction::Stinger) {
+            play_final_stinger(&mut commands, &server);
         }
     }
 }
@@ -107,116 +286,330 @@ fn handle_music_playback(
     audio: Res<Audio>,
     mut music: ResMut<Music>,
     assets: Res<AssetServer>,
-    time: Res<Time>,
+    music_assets: Res<MusicAssets>,
+    manifests: Res<Assets<MusicManifest>>,
+    instances: Res<Assets<AudioInstance>>,
     mut event_reader: EventReader<MusicEvent>,
 ) {
-    let loop_start: f64 = 6.15;
-    let loop_end: f64 = 60. + 27.592;
-    let day_path = "audio/court-day.wav";
-    let dream_path = "audio/court-dream.wav";
-    let start_tween = AudioTween::new(
-        std::time::Duration::from_millis(100),
-        AudioEasing::InPowi(2),
-    );
+    let Some(manifest) = manifests.get(&music_assets.manifest) else {
+        return;
+    };
 
-    if !music.playing {
-        let last = event_reader
-            .read()
-            .filter(|e| matches!(e, MusicEvent::FadeInSecs(_, _) | MusicEvent::Play(_)))
-            .last();
-
-        match last {
-            Some(MusicEvent::Play(MusicKind::Day)) => {
-                music.playing = true;
-                music.kind = MusicKind::Day;
-                audio
-                    .play(assets.load(day_path))
-                    .with_volume(MUSIC_VOL)
-                    .start_from(music.position)
-                    .fade_in(AudioTween::new(
-                        std::time::Duration::from_millis(50),
-                        AudioEasing::OutPowi(2),
-                    ));
-            }
-            Some(MusicEvent::FadeInSecs(MusicKind::Day, s)) => {
-                music.playing = true;
-                music.kind = MusicKind::Day;
-                audio
-                    .play(assets.load(day_path))
-                    .with_volume(MUSIC_VOL)
-                    .start_from(music.position)
-                    .fade_in(AudioTween::new(
-                        std::time::Duration::from_secs_f32(*s),
-                        AudioEasing::OutPowi(2),
-                    ));
+    for event in event_reader.read() {
+        let (kind, secs) = match event {
+            MusicEvent::Play(kind) => (kind, 0.05),
+            MusicEvent::CrossfadeTo(kind, secs) => (kind, *secs),
+            MusicEvent::Pause => {
+                music.resume = music.current().map(|current| {
+                    let position = instances
+                        .get(&current.handle)
+                        .and_then(|instance| instance.state().position())
+                        .unwrap_or_default();
+                    (current.kind.clone(), position)
+                });
+                music.fade_out(0.05);
+                continue;
             }
-            Some(MusicEvent::Play(MusicKind::Dream)) => {
-                music.playing = true;
-                music.kind = MusicKind::Dream;
-                audio
-                    .play(assets.load(dream_path))
-                    .with_volume(MUSIC_VOL)
-                    .looped()
-                    .fade_in(AudioTween::new(
-                        std::time::Duration::from_millis(750),
-                        AudioEasing::OutPowi(2),
-                    ));
+            MusicEvent::FadeOutSecs(secs) => {
+                music.resume = None;
+                music.fade_out(*secs);
+                continue;
             }
-            Some(MusicEvent::FadeInSecs(MusicKind::Dream, s)) => {
-                music.playing = true;
-                music.kind = MusicKind::Dream;
-                audio
-                    .play(assets.load(dream_path))
-                    .with_volume(MUSIC_VOL)
-                    .looped()
-                    .fade_in(AudioTween::new(
-                        std::time::Duration::from_secs_f32(*s),
-                        AudioEasing::OutPowi(2),
-                    ));
+        };
+
+        let rate = fade_rate(secs);
+        let mut found = false;
+        let mut instances = std::mem::take(&mut music.instances);
+        instances.sort_by(|a, b| b.phase.total_cmp(&a.phase));
+        for instance in instances.iter_mut() {
+            if !found && instance.kind == *kind {
+                found = true;
+                instance.rate = rate;
+            } else {
+                instance.rate = -rate;
             }
-            _ => {}
         }
-    } else {
-        if music.kind == MusicKind::Day {
-            music.position += time.delta_seconds_f64();
+        music.instances = instances;
+
+        if found {
+            continue;
+        }
+
+        let Some(track) = manifest.tracks.get(kind) else {
+            error!("no music track with id {kind:?}");
+            continue;
+        };
+
+        let start = match music.resume.take() {
+            Some((paused, position)) if paused == *kind => position,
+            _ => 0.,
+        };
+
+        let play = |path: &str| {
+            let mut command = audio.play(assets.load(path.to_string()));
+            command.with_volume(0.).start_from(start);
+            if let Some(region) = track.loop_region {
+                command.loop_from(region.start);
+                if let Some(end) = region.end {
+                    command.loop_until(end);
+                }
+            }
+
+            command.handle()
+        };
+
+        music.instances.push(MusicInstance {
+            kind: kind.clone(),
+            handle: play(&track.path),
+            volume: track.volume,
+            phase: 0.,
+            rate,
+            applied: None,
+            bpm: track.bpm,
+            stems: track
+                .stems
+                .iter()
+                .map(|stem| StemInstance {
+                    layer: stem.layer,
+                    handle: play(&stem.path),
+                    volume: stem.volume,
+                    applied: None,
+                })
+                .collect(),
+        });
+    }
+}
+
+fn follow_heart_size(
+    mut music: ResMut<Music>,
+    state: Option<Res<KingdomState>>,
+    mut reader: EventReader<NewHeartSize>,
+) {
+    let Some(state) = state else {
+        return;
+    };
+    let size = match reader.read().last() {
+        Some(NewHeartSize(size)) => *size,
+        // A new game starts without an event.
+        None if state.is_added() => state.heart_size,
+        None => return,
+    };
+
+    let fraction = state.profile.heart_fraction(size);
+    music.target_tension = (fraction * 2. - 1.).abs().clamp(0., 1.) as f64;
+}
+
+/// Holds every track, stem and the heartbeat where they are while virtual time is paused.
+fn hold_music(
+    music: Res<Music>,
+    time: Res<Time<Virtual>>,
+    mut held: Local<bool>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    if time.is_paused() == *held {
+        return;
+    }
+    *held = time.is_paused();
+
+    let handles = music
+        .instances
+        .iter()
+        .flat_map(|instance| {
+            std::iter::once(&instance.handle).chain(instance.stems.iter().map(|s| &s.handle))
+        })
+        .chain(music.heartbeat.iter().map(|heartbeat| &heartbeat.handle));
+
+    for handle in handles {
+        let Some(instance) = instances.get_mut(handle) else {
+            continue;
+        };
+
+        if *held {
+            instance.pause(AudioTween::default());
         } else {
-            music.position = 0.;
-        }
-
-        let last_event = event_reader
-            .read()
-            .filter(|e| !matches!(e, MusicEvent::Play(_) | MusicEvent::FadeInSecs(_, _)))
-            .last();
-
-        match last_event {
-            Some(MusicEvent::Pause) => {
-                music.playing = false;
-                audio.stop().fade_out(AudioTween::new(
-                    std::time::Duration::from_millis(50),
-                    AudioEasing::OutPowi(2),
-                ));
-                return;
+            instance.resume(AudioTween::default());
+        }
+    }
+}
+
+/// Advances each instance's fade and sets its volume to its manifest volume times its fade
+/// times the music bus, and its stems' likewise times their layer's level. Instances that
+/// have faded out are stopped along with their stems.
+fn apply_music_volume(
+    mut music: ResMut<Music>,
+    mixer: Res<Mixer>,
+    time: Res<Time>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    let bus = mixer.music_volume();
+    let delta = time.delta_seconds_f64();
+
+    // Layers follow the heart over a couple of seconds rather than jumping.
+    let step = 0.5 * delta;
+    music.tension += (music.target_tension - music.tension).clamp(-step, step);
+    let tension = music.tension;
+
+    music.instances.retain_mut(|music_instance| {
+        music_instance.phase = (music_instance.phase + music_instance.rate * delta).clamp(0., 1.);
+
+        // The instance only exists once kira has started it, so keep it until then.
+        let Some(instance) = instances.get_mut(&music_instance.handle) else {
+            return true;
+        };
+
+        if music_instance.phase == 0. && music_instance.rate < 0. {
+            instance.stop(AudioTween::default());
+            for stem in music_instance.stems.iter() {
+                if let Some(instance) = instances.get_mut(&stem.handle) {
+                    instance.stop(AudioTween::default());
+                }
             }
-            Some(MusicEvent::FadeOutSecs(s)) => {
-                audio.stop().fade_out(AudioTween::new(
-                    std::time::Duration::from_secs_f32(*s),
-                    AudioEasing::OutPowi(2),
-                ));
-                music.playing = false;
-                music.position = 0.;
-                return;
+            return false;
+        }
+
+        let gain = music_instance.gain() * bus;
+        let volume = music_instance.volume * gain;
+        if music_instance.applied != Some(volume) {
+            instance.set_volume(volume, AudioTween::default());
+            music_instance.applied = Some(volume);
+        }
+
+        for stem in music_instance.stems.iter_mut() {
+            let volume = stem.volume * stem.layer.level(tension) * gain;
+            if stem.applied == Some(volume) {
+                continue;
+            }
+
+            if let Some(instance) = instances.get_mut(&stem.handle) {
+                instance.set_volume(volume, AudioTween::default());
+                stem.applied = Some(volume);
             }
-            _ => {}
         }
 
-        if music.position >= loop_end && music.kind == MusicKind::Day {
-            audio
-                .play(assets.load(day_path))
-                .with_volume(MUSIC_VOL)
-                .start_from(loop_start)
-                .fade_in(start_tween);
+        true
+    });
+}
 
-            music.position = loop_start;
+/// Plays the manifest's heartbeat under the current track, louder and faster the greater
+/// the tension. Its tempo is the heart's own, moved to the nearest half, whole or double of
+/// the track's bpm when the track has one so that the two stay in time.
+fn play_heartbeat(
+    mut music: ResMut<Music>,
+    mixer: Res<Mixer>,
+    audio: Res<Audio>,
+    assets: Res<AssetServer>,
+    music_assets: Res<MusicAssets>,
+    manifests: Res<Assets<MusicManifest>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(heartbeat) = manifests
+        .get(&music_assets.manifest)
+        .and_then(|manifest| manifest.heartbeat.as_ref())
+    else {
+        return;
+    };
+
+    let (gain, track_bpm) = music
+        .current()
+        .map(|current| (current.gain(), current.bpm))
+        .unwrap_or_default();
+    let volume = heartbeat.volume * music.tension * gain * mixer.music_volume();
+
+    if volume <= 0. {
+        if let Some(playing) = music.heartbeat.take() {
+            if let Some(instance) = instances.get_mut(&playing.handle) {
+                instance.stop(AudioTween::default());
+            }
         }
+        return;
+    }
+
+    let heart_bpm = 70. + 70. * music.tension;
+    let bpm = match track_bpm {
+        Some(track_bpm) => [0.5, 1., 2.]
+            .map(|multiple| track_bpm * multiple)
+            .into_iter()
+            .min_by(|a, b| (a - heart_bpm).abs().total_cmp(&(b - heart_bpm).abs()))
+            .unwrap_or(heart_bpm),
+        None => heart_bpm,
+    };
+    let rate = bpm / heartbeat.bpm;
+
+    let playing = music.heartbeat.get_or_insert_with(|| HeartbeatInstance {
+        handle: audio
+            .play(assets.load(heartbeat.path.clone()))
+            .with_volume(0.)
+            .looped()
+            .handle(),
+        applied: None,
+    });
+
+    if playing.applied == Some((volume, rate)) {
+        return;
+    }
+
+    if let Some(instance) = instances.get_mut(&playing.handle) {
+        instance.set_volume(volume, AudioTween::default());
+        instance.set_playback_rate(rate, AudioTween::default());
+        playing.applied = Some((volume, rate));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_court_manifest() {
+        let manifest: MusicManifest =
+            serde_yaml::from_str(include_str!("../assets/audio/court.music.yaml")).unwrap();
+
+        assert_eq!(manifest.heartbeat.unwrap().bpm, 60.);
+        let day = &manifest.tracks[&MusicKind::DAY];
+        let day_loop = day.loop_region.unwrap();
+        assert_eq!((day_loop.start, day_loop.end), (6.15, Some(87.592)));
+        let dream = &manifest.tracks[&MusicKind::DREAM];
+        assert_eq!(dream.loop_region.unwrap().end, None);
+    }
+
+    #[test]
+    fn fills_in_defaults() {
+        let manifest: MusicManifest = serde_yaml::from_str(
+            "
+tracks:
+  day:
+    path: audio/court-day.wav
+    stems:
+      - { path: audio/court-day-percussion.wav, layer: percussion }
+",
+        )
+        .unwrap();
+
+        assert!(manifest.heartbeat.is_none());
+        let day = &manifest.tracks[&MusicKind::DAY];
+        assert!(day.loop_region.is_none());
+        assert_eq!(day.volume, 1.);
+        assert_eq!(day.stems[0].layer, StemLayer::Percussion);
+        assert_eq!(day.stems[0].volume, 1.);
+    }
+
+    #[test]
+    fn crossfades_at_equal_power() {
+        assert_eq!(equal_power(0.), 0.);
+        assert!((equal_power(1.) - 1.).abs() < 1e-9);
+
+        for step in 0..=10 {
+            let phase = step as f64 / 10.;
+            let power = equal_power(phase).powi(2) + equal_power(1. - phase).powi(2);
+            assert!((power - 1.).abs() < 1e-9, "power {power} at {phase}");
+        }
+    }
+
+    #[test]
+    fn fades_in_monotonically() {
+        let gains: Vec<f64> = (0..=10)
+            .map(|step| equal_power(step as f64 / 10.))
+            .collect();
+        assert!(gains.windows(2).all(|pair| pair[0] < pair[1]));
     }
 }
@@ -0,0 +1,426 @@
+use crate::ui::hex_to_vec4;
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+    render::view::RenderLayers,
+    utils::HashMap,
+};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use bevy_hanabi::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<ParticleManifest>::new(&[
+            "particles.yaml",
+        ]))
+        .add_systems(Update, (emit_cpu_particles, update_cpu_particles));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(HanabiPlugin);
+    }
+}
+
+/// Particle effects, keyed by the name they are spawned with through [`Particles::spawn`].
+///
+/// ```yaml
+/// presets:
+///   dream-embers:
+///     capacity: 500
+///     rate: 10
+///     lifetime: 4
+///     z_layer: -20
+///     shape: !sphere { center: [-100, 0, 0], radius: 80 }
+///     velocity: !fixed [100, -10, 0]
+///     size: [1, 2]
+///     gradient:
+///       - { at: 0.0, color: 0xff0000 }
+///       - { at: 1.0, color: 0xff0000, alpha: 0.0 }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct ParticleManifest {
+    pub presets: HashMap<String, ParticlePreset>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ParticlePreset {
+    /// Most particles alive at once.
+    pub capacity: u32,
+    /// Particles spawned per second.
+    pub rate: f32,
+    /// Seconds each particle lives.
+    pub lifetime: f32,
+    /// Simulate particles relative to the emitter, so they move with it.
+    #[serde(default)]
+    pub local: bool,
+    /// Draw order among 2D sprites.
+    #[serde(default)]
+    pub z_layer: Option<f32>,
+    pub shape: EmitterShape,
+    pub velocity: ParticleVelocity,
+    /// Smallest and largest particle size.
+    pub size: [f32; 2],
+    /// Colour over each particle's life.
+    pub gradient: Vec<GradientKey>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EmitterShape {
+    /// Particles start on the surface of a sphere.
+    Sphere { center: Vec3, radius: f32 },
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticleVelocity {
+    /// Every particle moves the same way.
+    Fixed(Vec3),
+    /// Away from a point.
+    Radial { center: Vec3, speed: f32 },
+    /// Around an axis through a point.
+    Tangent {
+        origin: Vec3,
+        axis: Vec3,
+        speed: f32,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct GradientKey {
+    /// How far through a particle's life, from 0 to 1.
+    pub at: f32,
+    /// An `0xRRGGBB` colour.
+    pub color: u32,
+    #[serde(default = "alpha_default")]
+    pub alpha: f32,
+}
+
+fn alpha_default() -> f32 {
+    1.
+}
+
+impl GradientKey {
+    fn color(&self) -> Vec4 {
+        hex_to_vec4(self.color).with_w(self.alpha)
+    }
+}
+
+impl ParticlePreset {
+    fn effect(&self) -> EffectAsset {
+        let mut module = Module::default();
+
+        let mut gradient = Gradient::new();
+        for key in self.gradient.iter() {
+            gradient.add_key(key.at, key.color());
+        }
+
+        let EmitterShape::Sphere { center, radius } = self.shape;
+        let init_pos = SetPositionSphereModifier {
+            center: module.lit(center),
+            radius: module.lit(radius),
+            dimension: ShapeDimension::Surface,
+        };
+        let init_lifetime =
+            SetAttributeModifier::new(Attribute::LIFETIME, module.lit(self.lifetime));
+        let init_size = SetSizeModifier {
+            size: CpuValue::Uniform((Vec2::splat(self.size[0]), Vec2::splat(self.size[1]))),
+        };
+        let simulation_space = if self.local {
+            SimulationSpace::Local
+        } else {
+            SimulationSpace::Global
+        };
+
+        let effect = |module: Module| {
+            EffectAsset::new(vec![self.capacity], Spawner::rate(self.rate.into()), module)
+                .with_simulation_space(simulation_space)
+                .init(init_pos)
+                .init(init_lifetime)
+                .render(init_size)
+                .render(ColorOverLifetimeModifier { gradient })
+        };
+
+        match self.velocity {
+            ParticleVelocity::Fixed(velocity) => {
+                let init_vel = SetAttributeModifier::new(Attribute::VELOCITY, module.lit(velocity));
+                effect(module).init(init_vel)
+            }
+            ParticleVelocity::Radial { center, speed } => {
+                let init_vel = SetVelocitySphereModifier {
+                    center: module.lit(center),
+                    speed: module.lit(speed),
+                };
+                effect(module).init(init_vel)
+            }
+            ParticleVelocity::Tangent {
+                origin,
+                axis,
+                speed,
+            } => {
+                let init_vel = SetVelocityTangentModifier {
+                    origin: module.lit(origin),
+                    axis: module.lit(axis),
+                    speed: module.lit(speed),
+                };
+                effect(module).init(init_vel)
+            }
+        }
+    }
+
+    /// A starting position and velocity, following the same rules as the hanabi effect.
+    fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        let z: f32 = rng.gen_range(-1.0..=1.0);
+        let theta = rng.gen_range(0.0..std::f32::consts::TAU);
+        let ring = (1. - z * z).sqrt();
+        let direction = Vec3::new(ring * theta.cos(), ring * theta.sin(), z);
+
+        let EmitterShape::Sphere { center, radius } = self.shape;
+        let position = center + direction * radius;
+
+        let velocity = match self.velocity {
+            ParticleVelocity::Fixed(velocity) => velocity,
+            ParticleVelocity::Radial { center, speed } => {
+                (position - center).normalize_or_zero() * speed
+            }
+            ParticleVelocity::Tangent {
+                origin,
+                axis,
+                speed,
+            } => axis.cross(position - origin).normalize_or_zero() * speed,
+        };
+
+        // Sprites are drawn by depth, so keep them on the emitter's plane.
+        (position.with_z(center.z), velocity)
+    }
+
+    fn color_at(&self, t: f32) -> Color {
+        let mut keys = self.gradient.iter();
+        let Some(mut previous) = keys.next() else {
+            return Color::WHITE;
+        };
+
+        let mut color = previous.color();
+        for key in keys {
+            if t <= key.at {
+                let span = (key.at - previous.at).max(f32::EPSILON);
+                color = previous
+                    .color()
+                    .lerp(key.color(), ((t - previous.at) / span).clamp(0., 1.));
+                break;
+            }
+            color = key.color();
+            previous = key;
+        }
+
+        Color::srgba(color.x, color.y, color.z, color.w)
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct ParticleAssets {
+    #[asset(path = "particles/court.particles.yaml")]
+    manifest: Handle<ParticleManifest>,
+}
+
+/// Spawns particle presets by name.
+#[derive(SystemParam)]
+pub struct Particles<'w> {
+    particle_assets: Res<'w, ParticleAssets>,
+    manifests: Res<'w, Assets<ParticleManifest>>,
+    effects: Option<ResMut<'w, Assets<EffectAsset>>>,
+}
+
+impl Particles<'_> {
+    /// Spawns the named preset as a hanabi effect, or as sprites simulated on the CPU where
+    /// hanabi is disabled.
+    pub fn spawn<'c>(
+        &mut self,
+        commands: &'c mut Commands,
+        name: &str,
+        transform: Transform,
+    ) -> Option<EntityCommands<'c>> {
+        let Some(preset) = self
+            .manifests
+            .get(&self.particle_assets.manifest)
+            .and_then(|manifest| manifest.presets.get(name))
+        else {
+            error!("no particle preset named {name}");
+            return None;
+        };
+
+        let Some(effects) = self.effects.as_mut() else {
+            return Some(commands.spawn((
+                SpatialBundle::from_transform(transform),
+                CpuEmitter {
+                    preset: preset.clone(),
+                    pending: 0.,
+                },
+            )));
+        };
+
+        let mut effect = ParticleEffect::new(effects.add(preset.effect()));
+        if let Some(z_layer) = preset.z_layer {
+            effect = effect.with_z_layer_2d(Some(z_layer));
+        }
+
+        Some(commands.spawn(ParticleEffectBundle {
+            effect,
+            transform,
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Component)]
+struct CpuEmitter {
+    preset: ParticlePreset,
+    /// Particles owed since the last one was spawned.
+    pending: f32,
+}
+
+#[derive(Component)]
+struct CpuParticle {
+    velocity: Vec3,
+    age: f32,
+}
+
+fn emit_cpu_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut emitters: Query<(
+        Entity,
+        &mut CpuEmitter,
+        Option<&Children>,
+        Option<&RenderLayers>,
+    )>,
+) {
+    for (entity, mut emitter, children, layers) in emitters.iter_mut() {
+        emitter.pending += emitter.preset.rate * time.delta_seconds();
+
+        let mut alive = children.map_or(0, |children| children.len());
+        while emitter.pending >= 1. {
+            emitter.pending -= 1.;
+            if alive >= emitter.preset.capacity as usize {
+                continue;
+            }
+            alive += 1;
+
+            let preset = &emitter.preset;
+            let (position, velocity) = preset.sample();
+            let size = rand::thread_rng().gen_range(preset.size[0]..=preset.size[1]);
+
+            let mut particle = commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: preset.color_at(0.),
+                        custom_size: Some(Vec2::splat(size)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(position),
+                    ..Default::default()
+                },
+                CpuParticle { velocity, age: 0. },
+            ));
+            if let Some(layers) = layers {
+                particle.insert(layers.clone());
+            }
+
+            let particle = particle.id();
+            commands.entity(entity).add_child(particle);
+        }
+    }
+}
+
+fn update_cpu_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    emitters: Query<&CpuEmitter>,
+    mut particles: Query<(
+        Entity,
+        &Parent,
+        &mut CpuParticle,
+        &mut Transform,
+        &mut Sprite,
+    )>,
+) {
+    for (entity, parent, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        let Ok(emitter) = emitters.get(parent.get()) else {
+            continue;
+        };
+
+        particle.age += time.delta_seconds();
+        if particle.age >= emitter.preset.lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        transform.translation += particle.velocity * time.delta_seconds();
+        sprite.color = emitter
+            .preset
+            .color_at(particle.age / emitter.preset.lifetime);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<ParticleManifest, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn parses_the_court_manifest() {
+        let manifest = parse(include_str!("../assets/particles/court.particles.yaml")).unwrap();
+
+        for name in [
+            "dream-embers",
+            "revolution-embers",
+            "menu-motes",
+            "rain",
+            "fog",
+        ] {
+            assert!(manifest.presets.contains_key(name), "missing {name}");
+        }
+
+        let rain = &manifest.presets["rain"];
+        assert!(matches!(rain.velocity, ParticleVelocity::Fixed(v) if v.y < 0.));
+        let snow = &manifest.presets["snow"];
+        assert!(matches!(snow.velocity, ParticleVelocity::Fixed(v) if v.y < 0. && v.x != 0.));
+        assert_eq!(rain.gradient[0].alpha, 0.8);
+        let leaves = &manifest.presets["leaves-red"];
+        assert!(matches!(leaves.velocity, ParticleVelocity::Tangent { .. }));
+        assert_eq!(leaves.gradient[0].alpha, 1.);
+    }
+
+    #[test]
+    fn needs_tagged_shapes_and_velocities() {
+        let preset = "
+presets:
+  motes:
+    capacity: 10
+    rate: 1
+    lifetime: 1
+    shape: SHAPE
+    velocity: !radial { center: [0, 0, 0], speed: 4 }
+    size: [1, 1]
+    gradient: []
+";
+
+        let manifest = parse(&preset.replace("SHAPE", "!sphere { center: [0, 0, 0], radius: 1 }"));
+        assert!(matches!(
+            manifest.unwrap().presets["motes"].velocity,
+            ParticleVelocity::Radial { speed, .. } if speed == 4.
+        ));
+        assert!(
+            parse(&preset.replace("SHAPE", "{ sphere: { center: [0, 0, 0], radius: 1 } }"))
+                .is_err()
+        );
+    }
+}
@@ -1,17 +1,19 @@
 use bevy::{
+    ecs::system::SystemParam,
     prelude::*,
     render::{
-        camera::RenderTarget,
+        camera::{RenderTarget, ScalingMode, Viewport},
         render_resource::{
             Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
         },
         view::RenderLayers,
     },
-    window::{PrimaryWindow, WindowResized},
+    window::PrimaryWindow,
 };
+use serde::{Deserialize, Serialize};
 use sickle_ui::ui_commands::UpdateStatesExt;
 
-use crate::{GameState, SkipRemove};
+use crate::{settings::Settings, GameState, SkipRemove};
 
 /// In-game resolution width.
 pub const RES_WIDTH: u32 = 240;
@@ -26,13 +28,33 @@ pub const PIXEL_PERFECT_LAYER: RenderLayers = RenderLayers::layer(0);
 /// Render layers for high-resolution rendering.
 pub const HIGH_RES_LAYER: RenderLayers = RenderLayers::layer(1);
 
+/// The window size the high-resolution UI is laid out for.
+pub const UI_DESIGN_SIZE: Vec2 = Vec2::new(1920., 1080.);
+
+/// How the canvas is scaled up to the window. Whatever it does not cover is letterboxed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CanvasScale {
+    /// The largest whole multiple of the canvas that fits, so every pixel is the same size.
+    Integer,
+    /// As large as fits while keeping the aspect ratio.
+    #[default]
+    Fit,
+    /// Fills the window, distorting the aspect ratio.
+    Stretch,
+}
+
+pub const CANVAS_SCALES: [CanvasScale; 3] =
+    [CanvasScale::Integer, CanvasScale::Fit, CanvasScale::Stretch];
+
 pub struct PixelPerfectPlugin;
 
 impl Plugin for PixelPerfectPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Msaa::Off)
+            .init_resource::<CanvasGrade>()
             .add_systems(Startup, setup_camera)
-            .add_systems(Update, fit_canvas);
+            .add_systems(Update, fit_canvas)
+            .add_systems(Update, grade_canvas.run_if(resource_changed::<CanvasGrade>));
     }
 }
 
@@ -41,6 +63,16 @@ impl Plugin for PixelPerfectPlugin {
 #[derive(Component)]
 struct Canvas;
 
+/// Tint multiplied over the whole pixel-perfect world.
+#[derive(Resource)]
+pub struct CanvasGrade(pub Color);
+
+impl Default for CanvasGrade {
+    fn default() -> Self {
+        Self(Color::WHITE)
+    }
+}
+
 /// Camera that renders the pixel-perfect world to the [`Canvas`].
 #[derive(Component)]
 pub struct InGameCamera;
@@ -49,6 +81,10 @@ pub struct InGameCamera;
 #[derive(Component)]
 pub struct OuterCamera;
 
+/// Camera that renders nothing, clearing the whole window so the letterbox bars stay black.
+#[derive(Component)]
+struct LetterboxCamera;
+
 fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
     let canvas_size = Extent3d {
         width: RES_WIDTH,
@@ -112,31 +148,121 @@ fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
 
     // the "outer" camera renders whatever is on `HIGH_RES_LAYERS` to the screen.
     // here, the canvas and one of the sample sprites will be rendered by this camera
+    // `fit_canvas` gives it a viewport the shape of the scaled canvas, which this fills.
     commands.spawn((
         Camera2dBundle {
-            // projection: OrthographicProjection {
-            //     scale: 1.0 / (2. as f32),
-            //     ..default()
-            // },
+            projection: OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: RES_WIDTH as f32,
+                    height: RES_HEIGHT as f32,
+                },
+                ..Camera2dBundle::default().projection
+            },
             ..Default::default()
         },
         SkipRemove,
         OuterCamera,
         HIGH_RES_LAYER,
     ));
+
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -2,
+                ..default()
+            },
+            ..default()
+        },
+        SkipRemove,
+        LetterboxCamera,
+        RenderLayers::none(),
+    ));
+}
+
+/// Finds where the cursor is in the pixel-perfect world, following it through the
+/// [`OuterCamera`], across the [`Canvas`] and back out through the [`InGameCamera`].
+#[derive(SystemParam)]
+pub struct PixelCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    outer_camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<OuterCamera>>,
+    canvas: Query<'w, 's, &'static GlobalTransform, With<Canvas>>,
+    in_game_camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<InGameCamera>>,
+}
+
+impl PixelCursor<'_, '_> {
+    pub fn world_position(&self) -> Option<Vec2> {
+        let cursor = self.windows.get_single().ok()?.cursor_position()?;
+
+        let (camera, transform) = self.outer_camera.get_single().ok()?;
+        let viewport = camera.logical_viewport_rect()?;
+        let outer = camera.viewport_to_world_2d(transform, cursor - viewport.min)?;
+
+        // The canvas sprite is one world unit per canvas pixel, centred on its transform.
+        let canvas = self.canvas.get_single().ok()?;
+        let local = canvas
+            .affine()
+            .inverse()
+            .transform_point3(outer.extend(0.))
+            .truncate();
+        let viewport = Vec2::new(
+            local.x + RES_WIDTH as f32 / 2.,
+            RES_HEIGHT as f32 / 2. - local.y,
+        );
+
+        let (camera, transform) = self.in_game_camera.get_single().ok()?;
+        camera.viewport_to_world_2d(transform, viewport)
+    }
+
+    /// The cursor as a fraction of the letterboxed canvas, from its top left corner.
+    pub fn canvas_fraction(&self) -> Option<Vec2> {
+        let cursor = self.windows.get_single().ok()?.cursor_position()?;
+        let (camera, _) = self.outer_camera.get_single().ok()?;
+        let viewport = camera.logical_viewport_rect()?;
+
+        Some((cursor - viewport.min) / viewport.size())
+    }
 }
 
-/// Scales camera projection to fit the window (integer multiples only).
+/// Letterboxes every camera drawing to the window to the scaled canvas, and sizes the UI
+/// to match.
 fn fit_canvas(
-    mut resize_events: EventReader<WindowResized>,
-    mut projections: Query<&mut OrthographicProjection, With<OuterCamera>>,
+    settings: Res<Settings>,
+    windows: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Camera, (Without<InGameCamera>, Without<LetterboxCamera>)>,
     mut ui_scale: ResMut<UiScale>,
 ) {
-    for event in resize_events.read() {
-        let h_scale = event.width / RES_WIDTH as f32;
-        let v_scale = event.height / RES_HEIGHT as f32;
-        let mut projection = projections.single_mut();
-        projection.scale = 1. / h_scale.min(v_scale);
-        ui_scale.0 = h_scale.min(v_scale) * (RES_WIDTH as f32 / 1920.);
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let camera_added = cameras.iter_mut().any(|camera| camera.is_added());
+    if !window.is_changed() && !settings.is_changed() && !camera_added {
+        return;
+    }
+
+    let size = window.physical_size().as_vec2();
+    let canvas = Vec2::new(RES_WIDTH as f32, RES_HEIGHT as f32);
+    let scale = match settings.canvas_scale {
+        CanvasScale::Integer => Vec2::splat((size / canvas).min_element().floor().max(1.)),
+        CanvasScale::Fit => Vec2::splat((size / canvas).min_element()),
+        CanvasScale::Stretch => size / canvas,
+    };
+    let drawn = (canvas * scale).min(size).floor().max(Vec2::ONE);
+    let position = ((size - drawn) / 2.).floor();
+
+    for mut camera in cameras.iter_mut() {
+        camera.viewport = Some(Viewport {
+            physical_position: position.as_uvec2(),
+            physical_size: drawn.as_uvec2(),
+            ..default()
+        });
+    }
+
+    ui_scale.0 = (drawn / window.scale_factor() / UI_DESIGN_SIZE).min_element();
+}
+
+fn grade_canvas(grade: Res<CanvasGrade>, mut canvas: Query<&mut Sprite, With<Canvas>>) {
+    for mut sprite in canvas.iter_mut() {
+        sprite.color = grade.0;
     }
 }
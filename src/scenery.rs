@@ -0,0 +1,274 @@
+use crate::{menu::ParallaxSprite, pixel_perfect::PIXEL_PERFECT_LAYER};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use rand::Rng;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+pub struct SceneryPlugin;
+
+impl Plugin for SceneryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<SceneryManifest>::new(&["scenery.yaml"]))
+            .add_event::<ShowScenery>()
+            .init_resource::<SceneryPace>()
+            .add_systems(Update, show_scenery)
+            .add_systems(FixedPreUpdate, (drift_layers, flip_layer_frames));
+    }
+}
+
+/// The background layers behind the court for each time of day and ending, keyed by
+/// [`SceneryKind`].
+///
+/// ```yaml
+/// scenes:
+///   court-day:
+///     - sprite: clouds.png
+///       position: [-256, 10]
+///       z: -100
+///       size: [1024, 176]
+///       tiled: true
+///       animation: !drift { speed: 0.5, wrap: 256 }
+///     - { sprite: town.png, z: -50, parallax: 0.001 }
+///     - sprite: crowd_layer_3.png
+///       z: -40
+///       parallax: 0.002
+///       animation:
+///         !frames { tile_size: [300, 160], columns: 2, rows: 1, frame_time: [1.2, 1.5] }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct SceneryManifest {
+    pub scenes: HashMap<SceneryKind, Vec<SceneryLayer>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SceneryLayer {
+    pub sprite: String,
+    #[serde(default)]
+    pub position: Vec2,
+    pub z: f32,
+    /// Stretches the sprite to this size, or repeats it when `tiled`.
+    #[serde(default)]
+    pub size: Option<Vec2>,
+    #[serde(default)]
+    pub tiled: bool,
+    /// How far the layer follows the cursor, as a [`ParallaxSprite`].
+    #[serde(default)]
+    pub parallax: Option<f32>,
+    #[serde(default)]
+    pub animation: Option<LayerAnimation>,
+}
+
+#[derive(Debug, Deserialize, Clone, Component)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerAnimation {
+    /// Scrolls right, wrapping back to `-wrap` once past `wrap`.
+    Drift { speed: f32, wrap: f32 },
+    /// Steps through a grid sheet, waiting a random time within `frame_time` between frames.
+    Frames {
+        tile_size: [u32; 2],
+        columns: u32,
+        rows: u32,
+        frame_time: [f32; 2],
+    },
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct SceneryAssets {
+    #[asset(path = "scenery/court.scenery.yaml")]
+    manifest: Handle<SceneryManifest>,
+}
+
+/// The id of a scene in the [`SceneryManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct SceneryKind(Cow<'static, str>);
+
+impl SceneryKind {
+    pub const COURT_DAY: Self = Self(Cow::Borrowed("court-day"));
+    pub const COURT_NIGHT: Self = Self(Cow::Borrowed("court-night"));
+    pub const REVOLUTION: Self = Self(Cow::Borrowed("revolution"));
+    pub const WIN: Self = Self(Cow::Borrowed("win"));
+}
+
+/// Replaces the current scenery with the given scene. Send it while the screen is faded out.
+#[derive(Debug, Event)]
+pub struct ShowScenery(pub SceneryKind);
+
+/// Scales how quickly frame-animated layers step, so the weather can slow the crowd down.
+#[derive(Resource)]
+pub struct SceneryPace(pub f32);
+
+impl Default for SceneryPace {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+#[derive(Component)]
+struct ScenerySprite;
+
+#[derive(Component, Deref, DerefMut)]
+struct FrameTimer(Timer);
+
+fn show_scenery(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    scenery_assets: Res<SceneryAssets>,
+    manifests: Res<Assets<SceneryManifest>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut reader: EventReader<ShowScenery>,
+    layers: Query<Entity, With<ScenerySprite>>,
+) {
+    let Some(ShowScenery(kind)) = reader.read().last() else {
+        return;
+    };
+
+    let Some(scene) = manifests
+        .get(&scenery_assets.manifest)
+        .and_then(|manifest| manifest.scenes.get(kind))
+    else {
+        error!("no scenery with id {kind:?}");
+        return;
+    };
+
+    for entity in layers.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for layer in scene.iter() {
+        let mut entity = commands.spawn((
+            SpriteBundle {
+                texture: server.load(layer.sprite.clone()),
+                transform: Transform::from_translation(layer.position.extend(layer.z)),
+                sprite: Sprite {
+                    custom_size: layer.size,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ScenerySprite,
+            PIXEL_PERFECT_LAYER,
+        ));
+
+        if layer.tiled {
+            entity.insert(ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 1.,
+            });
+        }
+
+        if let Some(parallax) = layer.parallax {
+            entity.insert(ParallaxSprite(parallax));
+        }
+
+        if let Some(animation) = &layer.animation {
+            if let LayerAnimation::Frames {
+                tile_size,
+                columns,
+                rows,
+                frame_time,
+            } = animation
+            {
+                let layout = TextureAtlasLayout::from_grid(
+                    UVec2::from(*tile_size),
+                    *columns,
+                    *rows,
+                    None,
+                    None,
+                );
+                entity.insert((
+                    TextureAtlas {
+                        layout: layouts.add(layout),
+                        index: 0,
+                    },
+                    FrameTimer(Timer::from_seconds(frame_time[0], TimerMode::Repeating)),
+                ));
+            }
+
+            entity.insert(animation.clone());
+        }
+    }
+}
+
+fn drift_layers(mut layers: Query<(&LayerAnimation, &mut Transform)>, time: Res<Time>) {
+    for (animation, mut transform) in layers.iter_mut() {
+        let LayerAnimation::Drift { speed, wrap } = animation else {
+            continue;
+        };
+
+        if transform.translation.x >= *wrap {
+            transform.translation.x = -wrap;
+        }
+        transform.translation.x += time.delta_seconds() * speed;
+    }
+}
+
+fn flip_layer_frames(
+    mut layers: Query<(&LayerAnimation, &mut FrameTimer, &mut TextureAtlas)>,
+    time: Res<Time>,
+    pace: Res<SceneryPace>,
+) {
+    for (animation, mut timer, mut atlas) in layers.iter_mut() {
+        let LayerAnimation::Frames {
+            columns,
+            rows,
+            frame_time,
+            ..
+        } = animation
+        else {
+            continue;
+        };
+
+        timer.tick(time.delta().mul_f32(pace.0));
+
+        if timer.finished() {
+            let secs = rand::thread_rng().gen_range(frame_time[0]..=frame_time[1]);
+            timer.set_duration(std::time::Duration::from_secs_f32(secs));
+            atlas.index = (atlas.index + 1) % (columns * rows) as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<SceneryManifest, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    #[test]
+    fn parses_the_court_manifest() {
+        let manifest = parse(include_str!("../assets/scenery/court.scenery.yaml")).unwrap();
+
+        for kind in [
+            SceneryKind::COURT_DAY,
+            SceneryKind::COURT_NIGHT,
+            SceneryKind::REVOLUTION,
+            SceneryKind::WIN,
+        ] {
+            assert!(manifest.scenes.contains_key(&kind), "missing {kind:?}");
+        }
+
+        let day = &manifest.scenes[&SceneryKind::COURT_DAY];
+        assert!(matches!(
+            day[0].animation,
+            Some(LayerAnimation::Drift { wrap, .. }) if wrap == 256.
+        ));
+        assert!(day[2..].iter().all(|layer| matches!(
+            layer.animation,
+            Some(LayerAnimation::Frames { columns: 2, .. })
+        )));
+    }
+
+    #[test]
+    fn needs_tagged_animations() {
+        let layer = "scenes: { win: [{ sprite: town.png, z: 0, animation: ANIMATION }] }";
+
+        assert!(parse(&layer.replace("ANIMATION", "!drift { speed: 1, wrap: 2 }")).is_ok());
+        assert!(parse(&layer.replace("ANIMATION", "{ drift: { speed: 1, wrap: 2 } }")).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{mixer::Bus, pixel_perfect::CanvasScale};
+use bevy::{
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode, WindowResolution},
+};
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostUpdate, apply_window_settings);
+    }
+}
+
+pub const WINDOW_MODES: [WindowMode; 3] = [
+    WindowMode::Windowed,
+    WindowMode::BorderlessFullscreen,
+    WindowMode::Fullscreen,
+];
+
+pub const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+/// Volume and display settings, persisted to [`Settings::FILE`] and applied as soon as
+/// they change.
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub voice_volume: f32,
+    pub ambience_volume: f32,
+    pub music_muted: bool,
+    pub sfx_muted: bool,
+    pub voice_muted: bool,
+    pub ambience_muted: bool,
+    pub window_mode: WindowMode,
+    pub resolution: (u32, u32),
+    pub canvas_scale: CanvasScale,
+    pub vsync: bool,
+}
+
+impl Settings {
+    pub const FILE: &'static str = "settings";
+
+    pub fn window(&self) -> Window {
+        let (width, height) = self.resolution;
+
+        Window {
+            title: "Concoeur".into(),
+            resolution: WindowResolution::new(width as f32, height as f32)
+                .with_scale_factor_override(1.0),
+            mode: self.window_mode,
+            present_mode: self.present_mode(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_muted(&self, bus: Bus) -> bool {
+        match bus {
+            Bus::Music => self.music_muted,
+            Bus::Sfx => self.sfx_muted,
+            Bus::Voice => self.voice_muted,
+            Bus::Ambience => self.ambience_muted,
+        }
+    }
+
+    pub fn muted_mut(&mut self, bus: Bus) -> &mut bool {
+        match bus {
+            Bus::Music => &mut self.music_muted,
+            Bus::Sfx => &mut self.sfx_muted,
+            Bus::Voice => &mut self.voice_muted,
+            Bus::Ambience => &mut self.ambience_muted,
+        }
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 1.,
+            sfx_volume: 1.,
+            voice_volume: 1.,
+            ambience_volume: 1.,
+            music_muted: false,
+            sfx_muted: false,
+            voice_muted: false,
+            ambience_muted: false,
+            window_mode: WindowMode::BorderlessFullscreen,
+            resolution: (1920, 1080),
+            canvas_scale: CanvasScale::default(),
+            vsync: true,
+        }
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    let (width, height) = settings.resolution;
+    window.mode = settings.window_mode;
+    window.resolution.set(width as f32, height as f32);
+    window.present_mode = settings.present_mode();
+}
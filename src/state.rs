@@ -1,13 +1,17 @@
 use crate::{
-    character::{Character, Characters, Request},
+    character::{choose_new_character, Character, Characters, Request, RequestAvailability},
+    config,
+    difficulty::{Difficulties, Difficulty, DifficultyProfile},
+    endless::Reign,
     time_state::TimeState,
     ui::decision::{Decision, DecisionType},
     ui::{ActiveMask, Mask},
     CharacterSet, GameState,
 };
-use bevy::prelude::*;
-pub use handlers::initialize_filters;
-use serde::Deserialize;
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use handlers::StoryFlags;
+pub use handlers::{initialize_filters, PrinceState, Story};
+use serde::{Deserialize, Serialize};
 use sickle_ui::ui_commands::UpdateStatesExt;
 
 mod handlers;
@@ -18,13 +22,14 @@ impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(handlers::HandlerPlugin)
             .add_event::<NewHeartSize>()
-            .insert_resource(KingdomState {
-                heart_size: 3.,
-                wealth: 50.,
-                happiness: 50.,
-                ..Default::default()
-            })
-            .add_systems(OnEnter(GameState::Main), startup)
+            .init_resource::<KingdomState>()
+            .add_systems(OnEnter(GameState::Main), startup.before(initialize_filters))
+            .add_systems(
+                OnEnter(TimeState::Day),
+                remember_day_start.before(choose_new_character),
+            )
+            .add_systems(OnEnter(GameState::Win), forget_day_start)
+            .add_systems(OnEnter(GameState::Loose), forget_day_start)
             .add_systems(
                 PostUpdate,
                 // TODO: check_end_conditions or its equivalent should be moved to a schedule _after_
@@ -34,30 +39,38 @@ impl Plugin for StatePlugin {
     }
 }
 
-fn startup(mut commands: Commands) {
-    commands.insert_resource(KingdomState {
-        heart_size: 3.,
-        wealth: 50.,
-        happiness: 50.,
-        ..Default::default()
-    });
+fn startup(
+    mut commands: Commands,
+    mut story: Story,
+    difficulty: Res<Difficulty>,
+    reign: Res<Reign>,
+    difficulties: Difficulties,
+) {
+    let Some(profile) = difficulties.profile(*difficulty) else {
+        return;
+    };
+
+    commands.insert_resource(KingdomState::new(profile.clone(), *reign));
+    story.reset();
 }
 
 pub const PROSPERITY_THRESHOLDS: [f32; 4] = [10., 20., 30., 40.];
-pub const MAX_HEART_SIZE: f32 = 6.;
 pub const MAX_WEALTH: f32 = 100.;
 pub const MAX_HAPPINESS: f32 = 100.;
-pub const MIN_PROSPERITY: f32 = 150.;
 pub const MAX_PROSPERITY: f32 = 200.;
 
-#[derive(Debug, Default, Asset, Resource, Reflect, Clone)]
+#[derive(Debug, Default, Asset, Resource, Reflect, Clone, Serialize, Deserialize)]
 pub struct KingdomState {
     pub heart_size: f32,
     pub wealth: f32,
     pub happiness: f32,
     pub can_use_insight: bool,
+    #[serde(skip)]
     pub last_decision: Option<DecisionType>,
     pub day: usize,
+    /// The rules of the difficulty the reign was started on.
+    pub profile: DifficultyProfile,
+    pub reign: Reign,
 }
 
 #[derive(Debug, Deserialize, Default, Asset, Resource, Reflect, Clone)]
@@ -73,6 +86,18 @@ pub struct StateUpdate {
 }
 
 impl KingdomState {
+    /// A reign's first day under `profile`.
+    pub fn new(profile: DifficultyProfile, reign: Reign) -> Self {
+        Self {
+            heart_size: profile.heart_size,
+            wealth: profile.wealth,
+            happiness: profile.happiness,
+            profile,
+            reign,
+            ..Default::default()
+        }
+    }
+
     pub fn apply_request_decision<'a>(
         &mut self,
         request: &'a Request,
@@ -84,9 +109,9 @@ impl KingdomState {
         };
 
         self.last_decision = Some(decision);
-        self.heart_size += result.heart_size;
-        self.happiness += result.happiness;
-        self.wealth += result.wealth;
+        self.heart_size += result.heart_size * self.profile.delta_multiplier;
+        self.happiness += result.happiness * self.profile.delta_multiplier;
+        self.wealth += result.wealth * self.profile.delta_multiplier;
 
         // remove me
         // self.heart_size = 5.;
@@ -106,20 +131,162 @@ impl KingdomState {
         Self::calculate_prosperity(self.happiness, self.wealth)
     }
 
+    pub fn is_endless(&self) -> bool {
+        matches!(self.reign, Reign::Endless { .. })
+    }
+
+    /// Whether the heart has reached either of its bounds.
+    pub fn heart_gave_out(&self) -> bool {
+        self.heart_size <= self.profile.min_heart_size
+            || self.heart_size >= self.profile.max_heart_size
+    }
+
     pub fn calculate_prosperity(happiness: f32, wealth: f32) -> f32 {
         happiness + wealth
     }
 
+    pub fn season(&self) -> Season {
+        // An endless reign goes around the seasons again.
+        match self.day % 3 {
+            1 => Season::Fall,
+            2 => Season::Winter,
+            _ => Season::Spring,
+        }
+    }
+
     pub fn day_name(&self) -> &'static str {
-        match self.day {
-            0 => "Spring",
-            1 => "Fall",
-            2 => "Winter",
-            _ => "Spring",
+        match self.season() {
+            Season::Spring => "Spring",
+            Season::Fall => "Fall",
+            Season::Winter => "Winter",
+        }
+    }
+
+    /// Shown as each day begins.
+    pub fn day_title(&self) -> String {
+        match self.reign {
+            Reign::Seasons => self.day_name().to_string(),
+            Reign::Endless { .. } => format!("Day {}", self.day + 1),
+        }
+    }
+
+    /// How many days an endless reign lasted, counting the one it ended on. Empty for the
+    /// seasons, which end on their own.
+    pub fn reign_summary(&self) -> String {
+        match (self.reign, self.day + 1) {
+            (Reign::Seasons, _) => String::new(),
+            (Reign::Endless { .. }, 1) => "Your reign lasted 1 day".to_string(),
+            (Reign::Endless { .. }, days) => format!("Your reign lasted {days} days"),
+        }
+    }
+}
+
+/// The season each day of the reign falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Season {
+    Spring,
+    Fall,
+    Winter,
+}
+
+/// The kingdom as it stood when the current day began, so the day can be played again. It
+/// is also the save the main menu continues from.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct DayStart {
+    state: KingdomState,
+    story: StoryFlags,
+    /// Which requests each character had been heard or filtered out, by character.
+    requests: HashMap<String, Vec<Vec<RequestAvailability>>>,
+}
+
+impl DayStart {
+    pub const FILE: &'static str = "save";
+
+    /// The saved day, if there is one.
+    pub fn load() -> Option<Self> {
+        config::load::<Option<Self>>(Self::FILE)
+    }
+
+    /// Forgets the saved day, once the reign is over or a new one begins.
+    pub fn remove() {
+        config::remove(Self::FILE);
+    }
+}
+
+/// The kingdom's stats, story flags and every character's requests, which a [`DayStart`]
+/// is taken from and put back into.
+#[derive(SystemParam)]
+pub struct Kingdom<'w> {
+    pub state: ResMut<'w, KingdomState>,
+    story: Story<'w>,
+    characters: Res<'w, Characters>,
+    character_assets: ResMut<'w, Assets<Character>>,
+    heart: EventWriter<'w, NewHeartSize>,
+}
+
+impl Kingdom<'_> {
+    fn day_start(&self) -> DayStart {
+        let requests = self
+            .characters
+            .table
+            .iter()
+            .filter_map(|(key, handle)| {
+                let character = self.character_assets.get(handle)?;
+                let days = character
+                    .requests
+                    .iter()
+                    .map(|day| day.iter().map(|r| r.availability.clone()).collect())
+                    .collect();
+
+                Some((key.to_string(), days))
+            })
+            .collect();
+
+        DayStart {
+            state: self.state.clone(),
+            story: self.story.flags(),
+            requests,
+        }
+    }
+
+    /// Puts back the stats, story flags and heard requests, and clears every character's
+    /// current request.
+    pub fn restore(&mut self, day_start: &DayStart) {
+        *self.state = day_start.state.clone();
+        self.story.restore(&day_start.story);
+        self.heart.send(NewHeartSize(self.state.heart_size));
+
+        for (key, handle) in self.characters.table.iter() {
+            let Some(character) = self.character_assets.get_mut(handle) else {
+                continue;
+            };
+            character.clear_request();
+
+            let Some(days) = day_start.requests.get(*key) else {
+                continue;
+            };
+            for (requests, availability) in character.requests.iter_mut().zip(days) {
+                for (request, availability) in requests.iter_mut().zip(availability) {
+                    request.availability = availability.clone();
+                }
+            }
         }
     }
 }
 
+/// Runs before the day's first character is chosen, so none of its requests are used yet.
+pub fn remember_day_start(mut commands: Commands, kingdom: Kingdom) {
+    let day_start = kingdom.day_start();
+    config::save(DayStart::FILE, &day_start);
+    commands.insert_resource(day_start);
+}
+
+fn forget_day_start(mut commands: Commands) {
+    commands.remove_resource::<DayStart>();
+    DayStart::remove();
+}
+
 #[derive(Component)]
 pub struct KingdomStateUi;
 
@@ -157,8 +324,9 @@ fn update_state(
                 .request(state.day)
                 .expect("Character presented with valid request");
             let update = state.apply_request_decision(request, decision.into());
+            let mask_update = update.mask;
 
-            if let Some(mask_update) = update.mask {
+            if let Some(mask_update) = mask_update {
                 active_mask.0 = mask_update;
             }
 
@@ -174,6 +342,10 @@ fn update_state(
                 }
             }
 
+            if let Some(mask_update) = mask_update {
+                character.mood = mask_update;
+            }
+
             writer.send(NewHeartSize(state.heart_size));
         }
     }
@@ -199,14 +371,143 @@ fn check_end_conditions(
     mut commands: Commands,
     time: Res<State<TimeState>>,
 ) {
-    if state.heart_size <= 0. || state.heart_size >= MAX_HEART_SIZE {
+    if state.heart_gave_out() {
         commands.next_state(GameState::Loose);
+    } else if state.is_endless() {
+        // The kingdom falls once it is bankrupt or in open revolt.
+        if state.wealth <= 0. || state.happiness <= 0. {
+            commands.next_state(GameState::Loose);
+        }
     } else if state.day == 3 && *time.get() == TimeState::Morning {
         info!("day 3 end condition check");
-        if state.prosperity() >= MIN_PROSPERITY {
+        if state.prosperity() >= state.profile.min_prosperity {
             commands.next_state(GameState::Win);
         } else {
             commands.next_state(GameState::Loose);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::DifficultyManifest;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn profile(difficulty: Difficulty) -> DifficultyProfile {
+        let manifest: DifficultyManifest =
+            serde_yaml::from_str(include_str!("../assets/difficulty/court.difficulty.yaml"))
+                .unwrap();
+        manifest.profile(difficulty).clone()
+    }
+
+    /// The state [`check_end_conditions`] moves the game to, if any.
+    fn outcome(state: &KingdomState, time: TimeState) -> Option<GameState> {
+        let mut world = World::new();
+        world.insert_resource(state.clone());
+        world.insert_resource(State::new(time));
+        world.init_resource::<NextState<GameState>>();
+        world.run_system_once(check_end_conditions);
+
+        match world.resource::<NextState<GameState>>() {
+            NextState::Pending(state) => Some(state.clone()),
+            NextState::Unchanged => None,
+        }
+    }
+
+    #[test]
+    fn wins_on_prosperity_after_the_last_season() {
+        for difficulty in Difficulty::ALL {
+            let mut state = KingdomState::new(profile(difficulty), Reign::Seasons);
+            state.day = 3;
+            state.wealth = state.profile.min_prosperity / 2.;
+            state.happiness = state.profile.min_prosperity / 2.;
+            assert_eq!(
+                outcome(&state, TimeState::Morning),
+                Some(GameState::Win),
+                "{difficulty:?}"
+            );
+
+            state.wealth -= 1.;
+            assert_eq!(
+                outcome(&state, TimeState::Morning),
+                Some(GameState::Loose),
+                "{difficulty:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn plays_on_before_the_last_season() {
+        for difficulty in Difficulty::ALL {
+            let mut state = KingdomState::new(profile(difficulty), Reign::Seasons);
+            state.day = 2;
+            assert_eq!(outcome(&state, TimeState::Morning), None, "{difficulty:?}");
+            state.day = 3;
+            assert_eq!(outcome(&state, TimeState::Day), None, "{difficulty:?}");
+        }
+    }
+
+    #[test]
+    fn loses_when_the_heart_reaches_either_bound() {
+        for difficulty in Difficulty::ALL {
+            let mut state = KingdomState::new(profile(difficulty), Reign::Seasons);
+            for bound in [state.profile.min_heart_size, state.profile.max_heart_size] {
+                state.heart_size = bound;
+                assert_eq!(
+                    outcome(&state, TimeState::Day),
+                    Some(GameState::Loose),
+                    "{difficulty:?} at {bound}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn endless_reigns_last_until_the_kingdom_falls() {
+        for difficulty in Difficulty::ALL {
+            let mut state = KingdomState::new(profile(difficulty), Reign::Endless { seed: 0 });
+            state.day = 3;
+            state.wealth = 1.;
+            state.happiness = 1.;
+            assert_eq!(outcome(&state, TimeState::Morning), None, "{difficulty:?}");
+
+            state.wealth = 0.;
+            assert_eq!(
+                outcome(&state, TimeState::Day),
+                Some(GameState::Loose),
+                "{difficulty:?} bankrupt"
+            );
+
+            state.wealth = 1.;
+            state.happiness = 0.;
+            assert_eq!(
+                outcome(&state, TimeState::Day),
+                Some(GameState::Loose),
+                "{difficulty:?} in revolt"
+            );
+
+            state.happiness = 1.;
+            state.heart_size = state.profile.max_heart_size;
+            assert_eq!(
+                outcome(&state, TimeState::Day),
+                Some(GameState::Loose),
+                "{difficulty:?} heart"
+            );
+        }
+    }
+
+    #[test]
+    fn counts_the_day_an_endless_reign_ends_on() {
+        let mut state = KingdomState::new(profile(Difficulty::Normal), Reign::Endless { seed: 0 });
+        assert_eq!(state.day_title(), "Day 1");
+        assert_eq!(state.reign_summary(), "Your reign lasted 1 day");
+
+        state.day = 4;
+        assert_eq!(state.day_title(), "Day 5");
+        assert_eq!(state.reign_summary(), "Your reign lasted 5 days");
+
+        state.reign = Reign::Seasons;
+        assert_eq!(state.reign_summary(), "");
+    }
+}
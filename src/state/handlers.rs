@@ -5,8 +5,12 @@ use crate::{
     ui::decision::DecisionType,
     GameState,
 };
-use bevy::{ecs::system::SystemId, prelude::*};
+use bevy::{
+    ecs::system::{SystemId, SystemParam},
+    prelude::*,
+};
 use foldhash::HashMap;
+use serde::{Deserialize, Serialize};
 
 pub struct HandlerPlugin;
 
@@ -63,6 +67,7 @@ handler_map! {
     nun_paganism,
     prince_festival_handler,
     prince_disabled_handler,
+    prince_drought_handler,
     // dream_transition_to_day,
     dream_summon,
     present_hand,
@@ -97,37 +102,46 @@ macro_rules! set_flag {
     };
 }
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct SmithyState {
     granted_strikers: Option<bool>,
 }
 
 set_flag!(smithy_strikers, SmithyState, granted_strikers);
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct NunState {
     made_paganism_illegal: Option<bool>,
 }
 
 set_flag!(nun_paganism, NunState, made_paganism_illegal);
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct PrinceState {
     approved_festival: Option<bool>,
     housed_disabled: Option<bool>,
+    relieved_drought: Option<bool>,
+}
+
+impl PrinceState {
+    /// Whether the royal reserves went to the south after the summer drought.
+    pub fn relieved_drought(&self) -> Option<bool> {
+        self.relieved_drought
+    }
 }
 
 set_flag!(prince_festival_handler, PrinceState, approved_festival);
 set_flag!(prince_disabled_handler, PrinceState, housed_disabled);
+set_flag!(prince_drought_handler, PrinceState, relieved_drought);
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct DuchyState {
     fined_duchy: Option<bool>,
 }
 
 set_flag!(fine_duchy_handler, DuchyState, fined_duchy);
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct PrincessState {
     lowered_conscription: Option<bool>,
     made_alliance: Option<bool>,
@@ -172,7 +186,7 @@ set_flag!(princess_alliance_handler, PrincessState, made_alliance);
 // DREAM
 /////////////////////////////
 
-#[derive(Debug, Default, Resource)]
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
 pub struct DreamState {
     said_summoned: Option<bool>,
     presented_hand: Option<bool>,
@@ -189,6 +203,55 @@ pub struct DreamState {
     done: bool,
 }
 
+/// A copy of every choice the story remembers, taken with [`Story::flags`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StoryFlags {
+    smithy: SmithyState,
+    nun: NunState,
+    prince: PrinceState,
+    duchy: DuchyState,
+    princess: PrincessState,
+    dream: DreamState,
+}
+
+/// The story states of every character, together.
+#[derive(SystemParam)]
+pub struct Story<'w> {
+    smithy: ResMut<'w, SmithyState>,
+    nun: ResMut<'w, NunState>,
+    prince: ResMut<'w, PrinceState>,
+    duchy: ResMut<'w, DuchyState>,
+    princess: ResMut<'w, PrincessState>,
+    dream: ResMut<'w, DreamState>,
+}
+
+impl Story<'_> {
+    pub fn flags(&self) -> StoryFlags {
+        StoryFlags {
+            smithy: self.smithy.clone(),
+            nun: self.nun.clone(),
+            prince: self.prince.clone(),
+            duchy: self.duchy.clone(),
+            princess: self.princess.clone(),
+            dream: self.dream.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, flags: &StoryFlags) {
+        *self.smithy = flags.smithy.clone();
+        *self.nun = flags.nun.clone();
+        *self.prince = flags.prince.clone();
+        *self.duchy = flags.duchy.clone();
+        *self.princess = flags.princess.clone();
+        *self.dream = flags.dream.clone();
+    }
+
+    /// Forgets every choice, for a new reign.
+    pub fn reset(&mut self) {
+        self.restore(&StoryFlags::default());
+    }
+}
+
 set_flag!(dream_summon, DreamState, said_summoned);
 // set_flag!(present_hand, DreamState, presented_hand);
 // set_flag!(present_hand, DreamState, presented_hand);
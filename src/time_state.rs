@@ -1,17 +1,18 @@
-use crate::animation::{AudioVolumeLens, FadeFromBlack, FadeToBlack};
+use crate::ambience::{AmbienceEvent, AmbienceKind};
+use crate::mixer::Bus;
 use crate::music::{MusicEvent, MusicKind};
-use crate::state::KingdomState;
-use crate::ui::background::{
-    setup_background_particles, setup_background_particles_for_dream, BackgroundTownNight,
-    CricketAudio, Crowd, CrowdAudio, CRICKET_VOLUME, CROWD_VOLUME,
+use crate::scenery::{SceneryKind, ShowScenery};
+use crate::state::{DayStart, Kingdom, KingdomState};
+use crate::transition::{
+    set_world_to_black, Transition, TransitionCommandsExt, TransitionEffect, Transitions,
 };
+use crate::ui::background::setup_background_particles_for_dream;
 use crate::ui::FONT_PATH;
 use crate::{GameState, SkipRemove};
 use bevy::audio::Volume;
+use bevy::ecs::system::{RunSystemOnce, SystemId};
 use bevy::prelude::*;
-use bevy_tweening::*;
 use sickle_ui::ui_commands::UpdateStatesExt;
-use std::time::Duration;
 
 pub struct TimeStatePlugin;
 
@@ -29,6 +30,9 @@ impl Plugin for TimeStatePlugin {
 }
 
 fn startup(mut commands: Commands, server: Res<AssetServer>) {
+    let id = commands.register_one_shot_system(setup_background_particles_for_dream);
+    commands.insert_resource(DreamParticles(id));
+
     commands
         .spawn((
             TextBundle::from_section(
@@ -58,6 +62,9 @@ fn increment_day(mut state: ResMut<KingdomState>) {
 #[derive(Component)]
 pub struct NextDayUi;
 
+#[derive(Resource)]
+pub struct DreamParticles(pub SystemId);
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
 pub enum TimeState {
     Night,
@@ -71,77 +78,44 @@ pub enum TimeState {
 fn enter_night(
     mut commands: Commands,
     mut music: EventWriter<MusicEvent>,
-    crowd_audio: Query<Entity, With<CrowdAudio>>,
-    cricket_audio: Query<Entity, With<CricketAudio>>,
+    mut ambience: EventWriter<AmbienceEvent>,
 ) {
-    let system = commands.register_one_shot_system(show_night);
-    commands.insert_resource(FadeToBlack::new(0.5, 10, 0., system));
-    music.send(MusicEvent::FadeOutSecs(5.));
-    commands
-        .entity(crowd_audio.single())
-        .insert(Animator::new(Tween::new(
-            EaseMethod::Linear,
-            Duration::from_secs_f32(5.),
-            AudioVolumeLens {
-                start: CROWD_VOLUME,
-                end: 0.,
-            },
-        )));
-    commands
-        .entity(cricket_audio.single())
-        .insert(Animator::new(Delay::new(Duration::from_secs_f32(3.)).then(
-            Tween::new(
-                EaseMethod::Linear,
-                Duration::from_secs_f32(5.),
-                AudioVolumeLens {
-                    start: 0.,
-                    end: CRICKET_VOLUME,
-                },
-            ),
-        )));
+    commands.transition(
+        Transition::to_black(5.)
+            .with_steps(10)
+            .with_effect(TransitionEffect::Iris)
+            .then_run(show_night),
+    );
+    music.send(MusicEvent::CrossfadeTo(MusicKind::DREAM, 8.));
+    ambience.send(AmbienceEvent::CrossfadeTo(AmbienceKind::COURT_NIGHT, 5.));
     info!("entering night");
 }
 
 pub fn start_in_night(
     mut commands: Commands,
-    mut nigth_village_sprite: Query<&mut Visibility, With<BackgroundTownNight>>,
-    mut crowds: Query<&mut Visibility, (With<Crowd>, Without<BackgroundTownNight>)>,
-    mut crowd_audio: Query<&mut PlaybackSettings, With<CrowdAudio>>,
-    mut cricket_audio: Query<&mut PlaybackSettings, (With<CricketAudio>, Without<CrowdAudio>)>,
+    mut scenery: EventWriter<ShowScenery>,
+    mut ambience: EventWriter<AmbienceEvent>,
+    dream_particles: Res<DreamParticles>,
 ) {
-    cricket_audio.single_mut().volume = Volume::new(CRICKET_VOLUME);
-    crowd_audio.single_mut().volume = Volume::new(0.);
-    let id = commands.register_one_shot_system(setup_background_particles_for_dream);
-    commands.run_system(id);
-    for mut sprite in nigth_village_sprite.iter_mut() {
-        *sprite = Visibility::Visible;
-    }
-    for mut vis in crowds.iter_mut() {
-        *vis = Visibility::Hidden;
-    }
+    ambience.send(AmbienceEvent::CrossfadeTo(AmbienceKind::COURT_NIGHT, 0.));
+    commands.run_system(dream_particles.0);
+    scenery.send(ShowScenery(SceneryKind::COURT_NIGHT));
     commands.next_state(TimeState::Night);
 }
 
 fn show_night(
     mut commands: Commands,
-    mut nigth_village_sprite: Query<&mut Visibility, With<BackgroundTownNight>>,
-    mut crowds: Query<&mut Visibility, (With<Crowd>, Without<BackgroundTownNight>)>,
-    mut music: EventWriter<MusicEvent>,
+    mut scenery: EventWriter<ShowScenery>,
+    dream_particles: Res<DreamParticles>,
 ) {
-    for mut sprite in nigth_village_sprite.iter_mut() {
-        *sprite = Visibility::Visible;
-    }
-    for mut vis in crowds.iter_mut() {
-        *vis = Visibility::Hidden;
-    }
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        let id = commands.register_one_shot_system(setup_background_particles_for_dream);
-        commands.run_system(id);
-    }
-    music.send(MusicEvent::FadeInSecs(crate::music::MusicKind::Dream, 3.));
-    let system = commands.register_one_shot_system(handle_night);
-    commands.insert_resource(FadeFromBlack::new(0.5, 10, 3., system));
+    scenery.send(ShowScenery(SceneryKind::COURT_NIGHT));
+    commands.run_system(dream_particles.0);
+    commands.transition(
+        Transition::from_black(5.)
+            .with_steps(10)
+            .with_delay(3.)
+            .then_run(handle_night),
+    );
 }
 
 fn handle_night(mut commands: Commands) {
@@ -152,19 +126,17 @@ fn handle_night(mut commands: Commands) {
 #[derive(Resource, Default)]
 pub struct DayNumberUi(Option<Timer>);
 
-fn enter_morning(
-    mut commands: Commands,
-    server: Res<AssetServer>,
-    mut music: EventWriter<MusicEvent>,
-) {
+fn enter_morning(mut commands: Commands, server: Res<AssetServer>) {
     info!("enter morning");
-    music.send(MusicEvent::FadeOutSecs(5.));
-    let system = commands.register_one_shot_system(handle_morning);
-    commands.insert_resource(FadeToBlack::new(0.5, 10, 0., system));
-    commands.spawn(AudioBundle {
-        source: server.load("audio/church_bells.wav"),
-        settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(0.5)),
-    });
+    commands.transition(
+        Transition::to_black(5.)
+            .with_steps(10)
+            .then_run(handle_morning),
+    );
+    commands.spawn(Bus::Sfx.sound(
+        server.load("audio/church_bells.wav"),
+        PlaybackSettings::DESPAWN.with_volume(Volume::new(0.5)),
+    ));
     // music.send(MusicEvent::FadeOutSecs(5.));
 }
 
@@ -173,67 +145,27 @@ pub fn handle_morning(
     mut next_day_ui: Query<(&mut Visibility, &mut Text), With<NextDayUi>>,
     mut day_number_ui: ResMut<DayNumberUi>,
     state: Res<KingdomState>,
-    crowd_audio: Query<Entity, With<CrowdAudio>>,
-    cricket_audio: Query<Entity, With<CricketAudio>>,
-    mut nigth_village_sprite: Query<
-        &mut Visibility,
-        (With<BackgroundTownNight>, Without<NextDayUi>),
-    >,
-    mut crowds: Query<
-        &mut Visibility,
-        (
-            With<Crowd>,
-            Without<BackgroundTownNight>,
-            Without<NextDayUi>,
-        ),
-    >,
+    mut ambience: EventWriter<AmbienceEvent>,
+    mut scenery: EventWriter<ShowScenery>,
     mut music: EventWriter<MusicEvent>,
 ) {
     info!("handle morning");
 
-    if let Ok(cricket_audio) = cricket_audio.get_single() {
-        commands
-            .entity(cricket_audio)
-            .insert(Animator::new(Tween::new(
-                EaseMethod::Linear,
-                Duration::from_secs_f32(5.),
-                AudioVolumeLens {
-                    start: CRICKET_VOLUME,
-                    end: 0.,
-                },
-            )));
-    }
-    commands.entity(crowd_audio.single()).insert(Animator::new(
-        Delay::new(Duration::from_secs_f32(3.)).then(Tween::new(
-            EaseMethod::Linear,
-            Duration::from_secs_f32(5.),
-            AudioVolumeLens {
-                start: 0.,
-                end: CROWD_VOLUME,
-            },
-        )),
-    ));
+    ambience.send(AmbienceEvent::CrossfadeTo(AmbienceKind::COURT_DAY, 5.));
 
-    for mut sprite in nigth_village_sprite.iter_mut() {
-        *sprite = Visibility::Hidden;
-    }
-    for mut vis in crowds.iter_mut() {
-        *vis = Visibility::Visible;
-    }
+    scenery.send(ShowScenery(SceneryKind::COURT_DAY));
 
     let (mut vis, mut text) = next_day_ui.single_mut();
     *vis = Visibility::Visible;
-    text.sections[0].value = state.day_name().to_string();
-
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        let id = commands.register_one_shot_system(setup_background_particles);
-        commands.run_system(id);
-    }
-
-    music.send(MusicEvent::FadeInSecs(MusicKind::Day, 5.));
-    let system = commands.register_one_shot_system(enter_day);
-    commands.insert_resource(FadeFromBlack::new(0.5, 4, 3., system));
+    text.sections[0].value = state.day_title();
+
+    music.send(MusicEvent::CrossfadeTo(MusicKind::DAY, 5.));
+    commands.transition(
+        Transition::from_black(2.)
+            .with_steps(4)
+            .with_delay(3.)
+            .then_run(enter_day),
+    );
     day_number_ui.0 = None;
 }
 
@@ -248,3 +180,27 @@ fn enter_day(
 
     commands.next_state(TimeState::Day);
 }
+
+/// Plays the current day again from its morning, as the kingdom stood when the day began.
+pub fn restart_day(
+    mut commands: Commands,
+    day_start: Option<Res<DayStart>>,
+    mut kingdom: Kingdom,
+    mut transitions: ResMut<Transitions>,
+) {
+    let Some(day_start) = day_start else {
+        warn!("no day has begun yet to restart");
+        return;
+    };
+
+    info!("restart day");
+    kingdom.restore(&day_start);
+
+    // Leave the day first, so entering it again chooses a new character.
+    transitions.reset();
+    commands.next_state(TimeState::None);
+    commands.add(|world: &mut World| {
+        world.run_system_once(set_world_to_black);
+        world.run_system_once(handle_morning);
+    });
+}
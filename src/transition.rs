@@ -0,0 +1,397 @@
+use crate::{
+    accessibility::Accessibility,
+    pixel_perfect::{RES_HEIGHT, RES_WIDTH},
+    ui::UiNode,
+    SkipRemove,
+};
+use bevy::{
+    ecs::system::RunSystemOnce,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use std::collections::VecDeque;
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transitions>()
+            .add_systems(Startup, spawn_cover)
+            .add_systems(Update, (run_transitions, draw_cover).chain());
+    }
+}
+
+/// How a [`Transition`] covers and uncovers the pixel canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEffect {
+    /// The whole canvas darkens at once.
+    Fade,
+    /// A black edge sweeps across from the left.
+    Wipe,
+    /// A circle closes on, or opens from, the centre.
+    Iris,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionDirection {
+    ToBlack,
+    FromBlack,
+}
+
+/// Quadratic curves from progress to how much of the canvas is covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    In,
+    Out,
+    InOut,
+}
+
+impl Easing {
+    pub fn ease(self, t: f32) -> f32 {
+        match self {
+            Easing::In => t * t,
+            Easing::Out => 1. - (1. - t).powi(2),
+            Easing::InOut if t < 0.5 => 2. * t * t,
+            Easing::InOut => 1. - 2. * (1. - t).powi(2),
+        }
+    }
+}
+
+type Callback = Box<dyn FnOnce(&mut Commands) + Send + Sync>;
+
+/// One cover or uncover of the canvas, queued with [`TransitionCommandsExt::transition`].
+///
+/// UI nodes fade evenly alongside it; the easing only shapes how the canvas is covered.
+pub struct Transition {
+    direction: TransitionDirection,
+    effect: TransitionEffect,
+    secs: f32,
+    delay: f32,
+    easing: Easing,
+    steps: Option<u32>,
+    on_complete: Vec<Callback>,
+}
+
+impl Transition {
+    fn new(direction: TransitionDirection, easing: Easing, secs: f32) -> Self {
+        Self {
+            direction,
+            effect: TransitionEffect::Fade,
+            secs,
+            delay: 0.,
+            easing,
+            steps: None,
+            on_complete: Vec::new(),
+        }
+    }
+
+    /// Covers the canvas over `secs`, quickly at first.
+    pub fn to_black(secs: f32) -> Self {
+        Self::new(TransitionDirection::ToBlack, Easing::Out, secs)
+    }
+
+    /// Uncovers the canvas over `secs`, slowly at first.
+    pub fn from_black(secs: f32) -> Self {
+        Self::new(TransitionDirection::FromBlack, Easing::In, secs)
+    }
+
+    pub fn with_effect(mut self, effect: TransitionEffect) -> Self {
+        self.effect = effect;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Waits `secs` after the previous transition before starting.
+    pub fn with_delay(mut self, secs: f32) -> Self {
+        self.delay = secs;
+        self
+    }
+
+    /// Moves in `steps` even jumps instead of smoothly, like the original fades.
+    pub fn with_steps(mut self, steps: u32) -> Self {
+        self.steps = Some(steps.max(1));
+        self
+    }
+
+    /// Calls `f` once the transition has finished.
+    pub fn then(mut self, f: impl FnOnce(&mut Commands) + Send + Sync + 'static) -> Self {
+        self.on_complete.push(Box::new(f));
+        self
+    }
+
+    /// Runs `system` once the transition has finished, without registering it with the world.
+    pub fn then_run<M>(self, system: impl IntoSystem<(), (), M> + Send + Sync + 'static) -> Self {
+        self.then(|commands| {
+            commands.add(move |world: &mut World| world.run_system_once(system));
+        })
+    }
+}
+
+/// How much of the canvas is covered, drawn by the [`TransitionCover`] sprite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cover {
+    effect: TransitionEffect,
+    direction: TransitionDirection,
+    /// From 0 for a clear canvas to 1 for a black one.
+    coverage: f32,
+}
+
+impl Cover {
+    const CLEAR: Self = Self {
+        effect: TransitionEffect::Fade,
+        direction: TransitionDirection::FromBlack,
+        coverage: 0.,
+    };
+
+    const BLACK: Self = Self {
+        effect: TransitionEffect::Fade,
+        direction: TransitionDirection::ToBlack,
+        coverage: 1.,
+    };
+
+    /// Whether the canvas pixel at `x`, `y` is black, for the shaped effects.
+    fn covers(&self, x: u32, y: u32) -> bool {
+        let size = Vec2::new(RES_WIDTH as f32, RES_HEIGHT as f32);
+        let pixel = Vec2::new(x as f32, y as f32) + 0.5;
+
+        match (self.effect, self.direction) {
+            (TransitionEffect::Fade, _) => true,
+            (TransitionEffect::Wipe, TransitionDirection::ToBlack) => {
+                pixel.x < self.coverage * size.x
+            }
+            (TransitionEffect::Wipe, TransitionDirection::FromBlack) => {
+                pixel.x >= (1. - self.coverage) * size.x
+            }
+            (TransitionEffect::Iris, _) => {
+                let radius = (1. - self.coverage) * size.length() / 2.;
+                pixel.distance(size / 2.) >= radius
+            }
+        }
+    }
+}
+
+struct Running {
+    transition: Transition,
+    elapsed: f32,
+    /// Progress already applied to the UI alpha.
+    shown: f32,
+}
+
+/// Transitions run one after another, so starting one never cancels another.
+#[derive(Resource)]
+pub struct Transitions {
+    queue: VecDeque<Transition>,
+    running: Option<Running>,
+    cover: Cover,
+}
+
+impl Default for Transitions {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            running: None,
+            cover: Cover::CLEAR,
+        }
+    }
+}
+
+impl Transitions {
+    pub fn push(&mut self, transition: Transition) {
+        self.queue.push_back(transition);
+    }
+
+    /// Drops every pending transition, without calling them back, and clears the canvas.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub trait TransitionCommandsExt {
+    /// Queues `transition` after any that are already pending.
+    fn transition(&mut self, transition: Transition) -> &mut Self;
+}
+
+impl TransitionCommandsExt for Commands<'_, '_> {
+    fn transition(&mut self, transition: Transition) -> &mut Self {
+        self.add(move |world: &mut World| {
+            world.resource_mut::<Transitions>().push(transition);
+        });
+        self
+    }
+}
+
+/// Covers the pixel canvas, below the high resolution sprites and the UI.
+#[derive(Component)]
+pub struct TransitionCover;
+
+fn spawn_cover(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mask = Image::new_fill(
+        Extent3d {
+            width: RES_WIDTH,
+            height: RES_HEIGHT,
+            ..default()
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgba(0.0, 0.0, 0.0, 0.0),
+                custom_size: Some(Vec2::new(RES_WIDTH as f32, RES_HEIGHT as f32)),
+                ..default()
+            },
+            texture: images.add(mask),
+            transform: Transform::from_xyz(0.0, 0.0, 900.0),
+            ..default()
+        },
+        TransitionCover,
+        SkipRemove,
+    ));
+}
+
+pub fn set_world_to_black(
+    mut ui_images: Query<&mut UiImage, With<UiNode>>,
+    mut ui_text: Query<&mut Text, With<UiNode>>,
+    mut transitions: ResMut<Transitions>,
+) {
+    for mut image in ui_images.iter_mut() {
+        image.color.set_alpha(0.);
+    }
+
+    for mut text in ui_text.iter_mut() {
+        for section in text.sections.iter_mut() {
+            let color = &mut section.style.color;
+            color.set_alpha(0.);
+        }
+    }
+
+    transitions.cover = Cover::BLACK;
+}
+
+fn run_transitions(
+    mut commands: Commands,
+    mut transitions: ResMut<Transitions>,
+    mut ui_images: Query<&mut UiImage, With<UiNode>>,
+    mut ui_text: Query<&mut Text, With<UiNode>>,
+    time: Res<Time>,
+    accessibility: Res<Accessibility>,
+) {
+    if transitions.running.is_none() && transitions.queue.is_empty() {
+        return;
+    }
+
+    let transitions = &mut *transitions;
+    if transitions.running.is_none() {
+        transitions.running = transitions.queue.pop_front().map(|transition| Running {
+            transition,
+            elapsed: 0.,
+            shown: 0.,
+        });
+    }
+    let Some(running) = transitions.running.as_mut() else {
+        return;
+    };
+
+    let transition = &mut running.transition;
+    if transition.delay > 0. {
+        transition.delay -= time.delta_seconds();
+        return;
+    }
+
+    running.elapsed += time.delta_seconds();
+    let mut progress = if accessibility.reduced_motion || transition.secs <= 0. {
+        1.
+    } else {
+        (running.elapsed / transition.secs).min(1.)
+    };
+    if let Some(steps) = transition.steps {
+        progress = (progress * steps as f32).floor() / steps as f32;
+    }
+
+    let change = progress - running.shown;
+    if change > 0. {
+        running.shown = progress;
+
+        let change = match transition.direction {
+            TransitionDirection::ToBlack => -change,
+            TransitionDirection::FromBlack => change,
+        };
+
+        for mut image in ui_images.iter_mut() {
+            let a = image.color.alpha();
+            image.color.set_alpha(a + change);
+        }
+
+        for mut text in ui_text.iter_mut() {
+            for section in text.sections.iter_mut() {
+                let color = &mut section.style.color;
+                let a = color.alpha();
+                color.set_alpha(a + change);
+            }
+        }
+    }
+
+    let eased = transition.easing.ease(progress);
+    transitions.cover = Cover {
+        effect: transition.effect,
+        direction: transition.direction,
+        coverage: match transition.direction {
+            TransitionDirection::ToBlack => eased,
+            TransitionDirection::FromBlack => 1. - eased,
+        },
+    };
+
+    if progress >= 1. {
+        if let Some(running) = transitions.running.take() {
+            for on_complete in running.transition.on_complete {
+                on_complete(&mut commands);
+            }
+        }
+    }
+}
+
+fn draw_cover(
+    transitions: Res<Transitions>,
+    mut drawn: Local<Option<Cover>>,
+    mut cover: Query<(&mut Sprite, &Handle<Image>), With<TransitionCover>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let next = transitions.cover;
+    let previous = drawn.replace(next);
+    if previous == Some(next) {
+        return;
+    }
+
+    let Ok((mut sprite, mask)) = cover.get_single_mut() else {
+        return;
+    };
+
+    // A fade darkens the whole sprite, so its mask only needs filling once.
+    let fade = next.effect == TransitionEffect::Fade;
+    sprite
+        .color
+        .set_alpha(if fade { next.coverage } else { 1. });
+    if fade && previous.is_some_and(|previous| previous.effect == TransitionEffect::Fade) {
+        return;
+    }
+
+    let Some(mask) = images.get_mut(mask) else {
+        *drawn = previous;
+        return;
+    };
+    for (i, pixel) in mask.data.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i as u32 % RES_WIDTH, i as u32 / RES_WIDTH);
+        pixel[3] = if next.covers(x, y) { 255 } else { 0 };
+    }
+}
@@ -1,10 +1,96 @@
-use bevy::prelude::*;
-use rand::Rng;
+use crate::mixer::Bus;
+use bevy::{prelude::*, text::TextLayoutInfo, utils::HashMap};
+use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
 use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub struct TypeWriterPlugin;
+
+impl Plugin for TypeWriterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            animate_glyphs.after(bevy::ui::widget::text_system),
+        );
+    }
+}
 
 #[derive(Debug, Event, Default)]
 struct TypeWriterTimeout;
 
+/// Per-glyph animation applied to a run of text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GlyphEffect {
+    #[default]
+    None,
+    Shake,
+    Wave,
+}
+
+/// A styled run of the plain text, in byte offsets.
+#[derive(Debug, Clone, PartialEq)]
+struct StyleRun {
+    range: Range<usize>,
+    color: Option<Color>,
+    effect: GlyphEffect,
+}
+
+/// Timing markup that takes effect once the reveal reaches its byte offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Directive {
+    Pause(f32),
+    Speed(f32),
+}
+
+pub const DEFAULT_BLIP: &str = "audio/interface/Wav/Cursor_tones/cursor_style_2.wav";
+
+/// One or more blip samples.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Blip {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Blip {
+    pub fn paths(&self) -> &[String] {
+        match self {
+            Self::One(path) => std::slice::from_ref(path),
+            Self::Many(paths) => paths,
+        }
+    }
+}
+
+/// How a character sounds while their text is revealed.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct Voice {
+    /// Sample played for every revealed glyph. If several are given, one is chosen at random.
+    pub blip: Blip,
+    /// Base playback speed of the blip.
+    pub pitch: f32,
+    /// Maximum random deviation from `pitch`.
+    pub pitch_variance: f32,
+    /// Seconds per revealed glyph.
+    pub speed: f32,
+    /// Extra seconds to wait after revealing one of these characters.
+    pub punctuation_pauses: HashMap<char, f32>,
+}
+
+impl Default for Voice {
+    fn default() -> Self {
+        Self {
+            blip: Blip::One(DEFAULT_BLIP.into()),
+            pitch: 1.,
+            pitch_variance: 0.05,
+            speed: 0.025,
+            punctuation_pauses: HashMap::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Resource)]
 pub struct TypeWriter {
     pub is_finished: bool,
@@ -12,53 +98,193 @@ pub struct TypeWriter {
     pub string: String,
     pub slice_range: Range<usize>,
     pub last_len: usize,
-    pub sfx: Handle<AudioSource>,
-    pub pitch_offset: f32,
+    pub sfx: Vec<Handle<AudioSource>>,
+    pub pitch: f32,
+    pub pitch_variance: f32,
+    pub punctuation_pauses: HashMap<char, f32>,
+    /// Byte offset at the end of each grapheme cluster.
+    graphemes: Vec<usize>,
+    /// Byte offsets where the text may be broken onto a new line.
+    breaks: Vec<usize>,
+    revealed: usize,
+    runs: Vec<StyleRun>,
+    directives: Vec<(usize, Directive)>,
+    next_directive: usize,
+    pause: Option<Timer>,
+    just_revealed: bool,
 }
 
 impl TypeWriter {
+    /// Create a new type writer from a line of markup.
+    ///
+    /// Supported tags are `[pause=secs]`, `[speed=secs]`, `[color=#rrggbb]..[/color]`,
+    /// `[shake]..[/shake]` and `[wave]..[/wave]`. Anything else in brackets is kept as text.
     pub fn new(string: String, speed: f32, sfx: Handle<AudioSource>) -> Self {
+        let (string, runs, directives) = parse_markup(string.trim());
+        let graphemes = string
+            .grapheme_indices(true)
+            .map(|(i, g)| i + g.len())
+            .collect();
+        let breaks = unicode_linebreak::linebreaks(&string)
+            .map(|(i, _)| i)
+            .collect();
+
         Self {
             timer: Timer::from_seconds(speed, TimerMode::Repeating),
-            string: string.trim().into(),
+            string,
             slice_range: 0..0,
             last_len: 0,
             is_finished: false,
-            pitch_offset: 0.,
-            sfx,
+            pitch: 1.,
+            pitch_variance: 0.05,
+            punctuation_pauses: HashMap::default(),
+            sfx: vec![sfx],
+            graphemes,
+            breaks,
+            revealed: 0,
+            runs,
+            directives,
+            next_directive: 0,
+            pause: None,
+            just_revealed: false,
+        }
+    }
+
+    /// Create a new type writer that speaks with `voice`.
+    ///
+    /// Values from character YAML that would stall the reveal or silence the blip are clamped,
+    /// and pauses that are not positive are skipped.
+    pub fn with_voice(string: String, voice: &Voice, server: &AssetServer) -> Self {
+        const MIN_SPEED: f32 = 0.001;
+        const MIN_PITCH: f32 = 0.05;
+
+        let default = Voice::default();
+        let finite_or = |value: f32, default: f32| {
+            if value.is_finite() {
+                value
+            } else {
+                default
+            }
+        };
+        let speed = finite_or(voice.speed, default.speed).max(MIN_SPEED);
+        // Keep the lowest pitch the blip can be played at above zero.
+        let pitch = finite_or(voice.pitch, default.pitch).max(MIN_PITCH);
+        let pitch_variance = finite_or(voice.pitch_variance.abs(), 0.).min(pitch - MIN_PITCH);
+        if (speed, pitch, pitch_variance) != (voice.speed, voice.pitch, voice.pitch_variance) {
+            warn!("clamping invalid voice: {voice:?}");
         }
+
+        let mut type_writer = Self::new(string, speed, Handle::default());
+        type_writer.sfx = voice
+            .blip
+            .paths()
+            .iter()
+            .map(|path| server.load(path.clone()))
+            .collect();
+        type_writer.pitch = pitch;
+        type_writer.pitch_variance = pitch_variance;
+        type_writer.punctuation_pauses = voice
+            .punctuation_pauses
+            .iter()
+            .filter(|(_, secs)| secs.is_finite() && **secs > 0.)
+            .map(|(c, secs)| (*c, *secs))
+            .collect();
+        type_writer
     }
 
     pub fn increment(&mut self, time: &Time) {
+        self.just_revealed = false;
+
+        if self.is_finished {
+            return;
+        }
+
+        self.apply_directives();
+
+        if let Some(pause) = self.pause.as_mut() {
+            pause.tick(time.delta());
+            if !pause.finished() {
+                return;
+            }
+            self.pause = None;
+        }
+
         self.timer.tick(time.delta());
 
         if self.timer.just_finished() {
-            self.last_len += 1;
-            if self.last_len >= self.string.len() {
+            self.revealed += 1;
+            if self.revealed >= self.graphemes.len() {
                 self.is_finished = true;
+                self.revealed = self.graphemes.len();
                 self.last_len = self.string.len();
+            } else {
+                self.last_len = self.graphemes[self.revealed - 1];
+                self.pause_on_punctuation();
             }
             self.slice_range = 0..self.last_len;
+            self.just_revealed = true;
+            self.apply_directives();
+        }
+    }
+
+    fn pause_on_punctuation(&mut self) {
+        let Some(c) = self.string[..self.last_len].chars().last() else {
+            return;
+        };
+
+        if let Some(secs) = self.punctuation_pauses.get(&c) {
+            self.pause = Some(Timer::from_seconds(*secs, TimerMode::Once));
+        }
+    }
+
+    /// Apply every directive located at or before the current reveal position.
+    fn apply_directives(&mut self) {
+        while let Some((position, directive)) = self.directives.get(self.next_directive) {
+            if *position > self.last_len {
+                break;
+            }
+
+            match *directive {
+                Directive::Pause(secs) => {
+                    self.pause = Some(Timer::from_seconds(secs, TimerMode::Once));
+                }
+                Directive::Speed(secs) => {
+                    self.timer
+                        .set_duration(std::time::Duration::from_secs_f32(secs));
+                }
+            }
+
+            self.next_directive += 1;
         }
     }
 
     pub fn try_play_sound(&self, commands: &mut Commands) {
-        if !self.is_finished && self.timer.just_finished() {
-            commands.spawn(AudioBundle {
-                source: self.sfx.clone(),
-                settings: PlaybackSettings {
-                    speed: rand::thread_rng()
-                        .gen_range((0.95 + self.pitch_offset)..(1.05 + self.pitch_offset)),
+        if self.is_finished || !self.just_revealed {
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+        if let Some(sfx) = self.sfx.choose(&mut rng) {
+            commands.spawn(Bus::Voice.sound(
+                sfx.clone(),
+                PlaybackSettings {
+                    speed: rng.gen_range(
+                        (self.pitch - self.pitch_variance)..=(self.pitch + self.pitch_variance),
+                    ),
                     mode: bevy::audio::PlaybackMode::Despawn,
                     ..Default::default()
                 },
-            });
+            ));
         }
     }
 
     pub fn finish(&mut self) {
         self.is_finished = true;
+        self.revealed = self.graphemes.len();
         self.last_len = self.string.len();
+        self.slice_range = 0..self.last_len;
+        self.next_directive = self.directives.len();
+        self.pause = None;
     }
 
     pub fn slice(&self) -> &str {
@@ -67,25 +293,363 @@ impl TypeWriter {
 
     pub fn slice_with_line_wrap(&self) -> String {
         let mut slice = self.string[self.slice_range.clone()].to_owned();
-        let padding = self
-            .string
-            .chars()
-            .enumerate()
-            .skip(slice.len())
-            .find_map(|(i, c)| {
-                if c == ' ' {
-                    Some(i - slice.len())
-                } else {
-                    None
+        slice.push_str(&self.line_wrap_padding());
+        slice
+    }
+
+    /// Spaces that reserve room for the word currently being revealed, so that it does not
+    /// jump to the next line halfway through.
+    ///
+    /// Words end at unicode line break opportunities, so text without spaces (such as CJK)
+    /// only reserves room for the cluster being revealed.
+    fn line_wrap_padding(&self) -> String {
+        if self.breaks.contains(&self.last_len) {
+            return String::new();
+        }
+
+        let Some(next_break) = self.breaks.iter().find(|b| **b > self.last_len) else {
+            return String::new();
+        };
+
+        let rest = self.string[self.last_len..*next_break].trim_end();
+        " ".repeat(rest.width())
+    }
+
+    /// Write the revealed text into `text` as one section per styled run.
+    ///
+    /// The font and size of the first section are used as the base style. Alpha is preserved
+    /// so that fades keep working across rewrites.
+    pub fn write_sections(&self, text: &mut Text, writer_text: &mut TypeWriterText) {
+        let mut base = text
+            .sections
+            .first()
+            .map(|s| s.style.clone())
+            .unwrap_or_default();
+        let alpha = base.color.alpha();
+        base.color = writer_text.color.with_alpha(alpha);
+
+        text.sections.clear();
+        writer_text.effects.clear();
+
+        for run in self.runs.iter() {
+            if run.range.start >= self.last_len {
+                break;
+            }
+
+            let end = run.range.end.min(self.last_len);
+            let mut style = base.clone();
+            if let Some(color) = run.color {
+                style.color = color.with_alpha(alpha);
+            }
+
+            text.sections
+                .push(TextSection::new(&self.string[run.range.start..end], style));
+            writer_text.effects.push(run.effect);
+        }
+
+        text.sections
+            .push(TextSection::new(self.line_wrap_padding(), base));
+        writer_text.effects.push(GlyphEffect::None);
+    }
+}
+
+/// Text entity driven by a [`TypeWriter`].
+///
+/// Stores the base color of the text and the effect of each section written by
+/// [`TypeWriter::write_sections`].
+#[derive(Debug, Component)]
+pub struct TypeWriterText {
+    pub color: Color,
+    effects: Vec<GlyphEffect>,
+    offsets: Vec<Vec2>,
+}
+
+impl Default for TypeWriterText {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+impl TypeWriterText {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            effects: Vec::new(),
+            offsets: Vec::new(),
+        }
+    }
+}
+
+const SHAKE_AMPLITUDE: f32 = 2.;
+const WAVE_AMPLITUDE: f32 = 4.;
+const WAVE_SPEED: f32 = 8.;
+const WAVE_PHASE: f32 = 0.6;
+
+/// Offset the laid out glyphs of animated sections.
+///
+/// Offsets are applied without triggering change detection, so a change to the layout means
+/// the text was laid out again and the glyphs are back at their resting positions.
+fn animate_glyphs(time: Res<Time>, mut texts: Query<(&mut TypeWriterText, &mut TextLayoutInfo)>) {
+    let mut rng = rand::thread_rng();
+    let t = time.elapsed_seconds();
+
+    for (writer_text, mut layout) in texts.iter_mut() {
+        let writer_text = writer_text.into_inner();
+
+        if layout.is_changed() {
+            writer_text.offsets.clear();
+        }
+
+        let glyphs = &mut layout.bypass_change_detection().glyphs;
+        writer_text.offsets.resize(glyphs.len(), Vec2::ZERO);
+
+        for (i, glyph) in glyphs.iter_mut().enumerate() {
+            let offset = match writer_text.effects.get(glyph.section_index) {
+                Some(GlyphEffect::Shake) => Vec2::new(
+                    rng.gen_range(-SHAKE_AMPLITUDE..SHAKE_AMPLITUDE),
+                    rng.gen_range(-SHAKE_AMPLITUDE..SHAKE_AMPLITUDE),
+                ),
+                Some(GlyphEffect::Wave) => Vec2::new(
+                    0.,
+                    (t * WAVE_SPEED + i as f32 * WAVE_PHASE).sin() * WAVE_AMPLITUDE,
+                ),
+                _ => Vec2::ZERO,
+            };
+
+            glyph.position += offset - writer_text.offsets[i];
+            writer_text.offsets[i] = offset;
+        }
+    }
+}
+
+/// A finite, non-negative number of seconds.
+fn parse_secs(value: &str) -> Option<f32> {
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.)
+}
+
+/// Strip markup from `input`, returning the plain text along with its styled runs and timing
+/// directives.
+fn parse_markup(input: &str) -> (String, Vec<StyleRun>, Vec<(usize, Directive)>) {
+    let mut string = String::with_capacity(input.len());
+    let mut runs = Vec::new();
+    let mut directives = Vec::new();
+
+    let mut color: Option<Color> = None;
+    let mut effect = GlyphEffect::None;
+    let mut run_start = 0;
+
+    let mut rest = input;
+    while let Some(open) = rest.find('[') {
+        string.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+
+        let tag = &rest[1..close];
+        let (next_color, next_effect) = match tag.split_once('=') {
+            Some(("pause", secs)) => match parse_secs(secs) {
+                Some(secs) => {
+                    directives.push((string.len(), Directive::Pause(secs)));
+                    (color, effect)
+                }
+                None => {
+                    warn!("invalid pause duration in type writer markup: '{tag}'");
+                    (color, effect)
                 }
-            })
-            .unwrap_or_else(|| 0);
-        if slice.chars().last() != Some(' ') {
-            for _ in 0..padding {
-                slice.push(' ');
+            },
+            Some(("speed", secs)) => match parse_secs(secs).filter(|secs| *secs > 0.) {
+                Some(secs) => {
+                    directives.push((string.len(), Directive::Speed(secs)));
+                    (color, effect)
+                }
+                None => {
+                    warn!("invalid speed in type writer markup: '{tag}'");
+                    (color, effect)
+                }
+            },
+            Some(("color", hex)) => match Srgba::hex(hex.trim()) {
+                Ok(c) => (Some(c.into()), effect),
+                Err(_) => {
+                    warn!("invalid color in type writer markup: '{tag}'");
+                    (color, effect)
+                }
+            },
+            _ => match tag {
+                "/color" => (None, effect),
+                "shake" => (color, GlyphEffect::Shake),
+                "wave" => (color, GlyphEffect::Wave),
+                "/shake" | "/wave" => (color, GlyphEffect::None),
+                _ => {
+                    // Not markup, keep the brackets as text.
+                    string.push('[');
+                    rest = &rest[1..];
+                    continue;
+                }
+            },
+        };
+
+        if next_color != color || next_effect != effect {
+            if string.len() > run_start {
+                runs.push(StyleRun {
+                    range: run_start..string.len(),
+                    color,
+                    effect,
+                });
             }
+            run_start = string.len();
+            color = next_color;
+            effect = next_effect;
         }
 
-        slice
+        rest = &rest[close + 1..];
+    }
+    string.push_str(rest);
+
+    if string.len() > run_start {
+        runs.push(StyleRun {
+            range: run_start..string.len(),
+            color,
+            effect,
+        });
+    }
+
+    (string, runs, directives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_writer(string: &str) -> TypeWriter {
+        TypeWriter::new(string.to_string(), 0.1, Handle::default())
+    }
+
+    /// Reveals `count` more grapheme clusters.
+    fn reveal(type_writer: &mut TypeWriter, count: usize) {
+        let mut time = Time::<()>::default();
+        for _ in 0..count {
+            time.advance_by(type_writer.timer.duration());
+            type_writer.increment(&time);
+        }
+    }
+
+    fn run(range: Range<usize>, color: Option<Color>, effect: GlyphEffect) -> StyleRun {
+        StyleRun {
+            range,
+            color,
+            effect,
+        }
+    }
+
+    #[test]
+    fn places_directives_at_their_byte_offset() {
+        let (string, _, directives) = parse_markup("Hé[pause=0.5] there[speed=0.1]!");
+
+        assert_eq!(string, "Hé there!");
+        assert_eq!(
+            directives,
+            [(3, Directive::Pause(0.5)), (9, Directive::Speed(0.1))]
+        );
+    }
+
+    #[test]
+    fn splits_runs_at_style_tags() {
+        let red = Some(Color::srgb(1., 0., 0.));
+        let (string, runs, _) = parse_markup("a[color=#ff0000]b[shake]c[/color]d[/shake]e[wave]f");
+
+        assert_eq!(string, "abcdef");
+        assert_eq!(
+            runs,
+            [
+                run(0..1, None, GlyphEffect::None),
+                run(1..2, red, GlyphEffect::None),
+                run(2..3, red, GlyphEffect::Shake),
+                run(3..4, None, GlyphEffect::Shake),
+                run(4..5, None, GlyphEffect::None),
+                run(5..6, None, GlyphEffect::Wave),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_tags_as_text() {
+        let (string, runs, directives) = parse_markup("a [b] [c=d] e");
+
+        assert_eq!(string, "a [b] [c=d] e");
+        assert_eq!(runs, [run(0..13, None, GlyphEffect::None)]);
+        assert!(directives.is_empty());
+    }
+
+    #[test]
+    fn keeps_an_unclosed_bracket() {
+        let (string, _, _) = parse_markup("wait [");
+        assert_eq!(string, "wait [");
+
+        let (string, _, _) = parse_markup("[wave]wait [shake");
+        assert_eq!(string, "wait [shake");
+    }
+
+    #[test]
+    fn drops_invalid_values() {
+        let (string, runs, directives) = parse_markup(
+            "[pause=-1]a[pause=NaN]b[speed=0]c[speed=-0.1]d[pause=inf]e[pause=x]f[color=#zz]g",
+        );
+
+        assert_eq!(string, "abcdefg");
+        assert_eq!(runs, [run(0..7, None, GlyphEffect::None)]);
+        assert!(directives.is_empty());
+    }
+
+    #[test]
+    fn reveals_combining_marks_with_their_base() {
+        let mut writer = type_writer("e\u{301}te\u{301}");
+        reveal(&mut writer, 1);
+        assert_eq!(writer.slice(), "e\u{301}");
+        reveal(&mut writer, 1);
+        assert_eq!(writer.slice(), "e\u{301}t");
+    }
+
+    #[test]
+    fn reveals_emoji_sequences_at_once() {
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let mut writer = type_writer(&format!("{family}!"));
+        reveal(&mut writer, 1);
+        assert_eq!(writer.slice(), family);
+        assert!(!writer.is_finished);
+        reveal(&mut writer, 1);
+        assert!(writer.is_finished);
+    }
+
+    #[test]
+    fn pads_the_word_being_revealed() {
+        let mut writer = type_writer("hello world");
+        reveal(&mut writer, 2);
+        assert_eq!(writer.slice_with_line_wrap(), "he   ");
+    }
+
+    #[test]
+    fn does_not_pad_at_a_break() {
+        let mut writer = type_writer("hello world");
+        reveal(&mut writer, 6);
+        assert_eq!(writer.slice_with_line_wrap(), "hello ");
+    }
+
+    #[test]
+    fn pads_by_display_width() {
+        // Every ideograph is a break opportunity, so none are padded.
+        let mut writer = type_writer("\u{65e5}\u{672c}\u{8a9e}");
+        reveal(&mut writer, 1);
+        assert_eq!(writer.slice_with_line_wrap(), "\u{65e5}");
+
+        // Combining marks take no room of their own.
+        let mut writer = type_writer("cafe\u{301} au lait");
+        reveal(&mut writer, 1);
+        assert_eq!(writer.slice_with_line_wrap(), "c   ");
     }
 }
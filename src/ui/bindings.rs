@@ -0,0 +1,264 @@
+use bevy::prelude::*;
+use sickle_ui::prelude::*;
+
+use crate::{
+    config,
+    controls::{Action, Binding, Bindings, Controls, LocksControls},
+};
+
+use super::FONT_PATH;
+
+/// An overlay listing every action and what it is bound to. Choosing a row waits for
+/// the next key or gamepad button and binds it to that action.
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BindingsCursor::default())
+            .add_event::<OpenBindingsScreen>()
+            .add_systems(
+                Update,
+                (
+                    toggle_bindings_screen,
+                    capture_binding,
+                    navigate_bindings,
+                    update_binding_rows,
+                )
+                    .chain(),
+            );
+    }
+}
+
+const FONT_SIZE: f32 = 30.;
+const TEXT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FOCUS_COLOR: Color = Color::WHITE;
+const CAPTURE_COLOR: Color = Color::srgb(1., 0.85, 0.4);
+
+/// Opens the bindings screen from another menu.
+#[derive(Event)]
+pub struct OpenBindingsScreen;
+
+#[derive(Component)]
+struct BindingsScreen;
+
+/// A row on the bindings screen. The row past the last binding resets to the defaults.
+#[derive(Component)]
+struct BindingRow(usize);
+
+#[derive(Default, Resource)]
+struct BindingsCursor {
+    row: usize,
+    capturing: bool,
+}
+
+fn open_bindings_screen(commands: &mut Commands, bindings: &Bindings, server: &AssetServer) {
+    let style = |color| TextStyle {
+        font_size: FONT_SIZE,
+        font: server.load(FONT_PATH),
+        color,
+    };
+
+    commands.ui_builder(UiRoot).container(
+        (
+            BindingsScreen,
+            LocksControls,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0., 0., 0., 0.9).into(),
+                z_index: ZIndex::Global(1000),
+                ..Default::default()
+            },
+        ),
+        |screen| {
+            screen.spawn(TextBundle::from_section("Controls\n", style(FOCUS_COLOR)));
+
+            for i in 0..=bindings.0.len() {
+                screen.spawn((
+                    BindingRow(i),
+                    Interaction::default(),
+                    TextBundle::from_sections([
+                        TextSection::new("", style(TEXT_COLOR)),
+                        TextSection::new("", style(TEXT_COLOR)),
+                    ]),
+                ));
+            }
+
+            screen.spawn(TextBundle::from_section(
+                "\nEscape to cancel, Delete to clear",
+                style(TEXT_COLOR),
+            ));
+        },
+    );
+}
+
+fn toggle_bindings_screen(
+    mut commands: Commands,
+    controls: Controls,
+    mut cursor: ResMut<BindingsCursor>,
+    screen: Query<Entity, With<BindingsScreen>>,
+    mut open: EventReader<OpenBindingsScreen>,
+    bindings: Res<Bindings>,
+    server: Res<AssetServer>,
+) {
+    let opened_elsewhere = open.read().count() > 0;
+
+    if cursor.capturing {
+        return;
+    }
+
+    if let Ok(entity) = screen.get_single() {
+        if controls.menu_just_pressed(Action::OpenBindings)
+            || controls.menu_just_pressed(Action::Pause)
+        {
+            commands.entity(entity).despawn_recursive();
+        }
+    } else if controls.just_pressed(Action::OpenBindings) || opened_elsewhere {
+        *cursor = BindingsCursor::default();
+        open_bindings_screen(&mut commands, &bindings, &server);
+    }
+}
+
+fn capture_binding(
+    mut cursor: ResMut<BindingsCursor>,
+    mut bindings: ResMut<Bindings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+) {
+    if !cursor.capturing {
+        return;
+    }
+
+    let Some(binding) = bindings.0.get_mut(cursor.row) else {
+        cursor.capturing = false;
+        return;
+    };
+
+    if let Some(key) = keys.get_just_pressed().next() {
+        match key {
+            KeyCode::Escape => {}
+            KeyCode::Delete | KeyCode::Backspace => {
+                binding.keys.clear();
+                binding.buttons.clear();
+            }
+            key => binding.keys = vec![*key],
+        }
+    } else if let Some(button) = buttons.get_just_pressed().next() {
+        binding.buttons = vec![button.button_type];
+    } else {
+        return;
+    }
+
+    cursor.capturing = false;
+    config::save(Bindings::FILE, &*bindings);
+}
+
+fn navigate_bindings(
+    mut cursor: ResMut<BindingsCursor>,
+    mut bindings: ResMut<Bindings>,
+    controls: Controls,
+    rows: Query<(&BindingRow, &Interaction), Changed<Interaction>>,
+    screen: Query<(), With<BindingsScreen>>,
+) {
+    if screen.is_empty() || cursor.capturing {
+        return;
+    }
+
+    let last = bindings.0.len();
+    let mut activate = controls.menu_just_pressed(Action::Advance);
+
+    if controls.menu_just_pressed(Action::Up) {
+        cursor.row = cursor.row.checked_sub(1).unwrap_or(last);
+    } else if controls.menu_just_pressed(Action::Down) {
+        cursor.row = if cursor.row >= last {
+            0
+        } else {
+            cursor.row + 1
+        };
+    }
+
+    for (row, interaction) in rows.iter() {
+        match interaction {
+            Interaction::Hovered => cursor.row = row.0,
+            Interaction::Pressed => {
+                cursor.row = row.0;
+                activate = true;
+            }
+            Interaction::None => {}
+        }
+    }
+
+    if !activate {
+        return;
+    }
+
+    if cursor.row == last {
+        *bindings = Bindings::default();
+        config::save(Bindings::FILE, &*bindings);
+    } else {
+        cursor.capturing = true;
+    }
+}
+
+fn update_binding_rows(
+    cursor: Res<BindingsCursor>,
+    bindings: Res<Bindings>,
+    mut rows: Query<(&BindingRow, &mut Text)>,
+    added: Query<(), Added<BindingRow>>,
+) {
+    if !cursor.is_changed() && !bindings.is_changed() && added.is_empty() {
+        return;
+    }
+
+    for (row, mut text) in rows.iter_mut() {
+        let focused = row.0 == cursor.row;
+        let color = match (focused, cursor.capturing) {
+            (true, true) => CAPTURE_COLOR,
+            (true, false) => FOCUS_COLOR,
+            _ => TEXT_COLOR,
+        };
+
+        let (label, bound) = match bindings.0.get(row.0) {
+            Some(binding) if focused && cursor.capturing => (
+                binding.action.label(),
+                "press a key or button...".to_string(),
+            ),
+            Some(binding) => (binding.action.label(), describe(binding)),
+            None => ("Reset to defaults".to_string(), String::new()),
+        };
+
+        text.sections[0].value = format!("{label:<20}");
+        text.sections[1].value = bound;
+        for section in text.sections.iter_mut() {
+            section.style.color = color;
+        }
+    }
+}
+
+fn describe(binding: &Binding) -> String {
+    let keys = binding.keys.iter().map(|key| {
+        let name = format!("{key:?}");
+        name.strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .unwrap_or(&name)
+            .to_string()
+    });
+    let buttons = binding
+        .buttons
+        .iter()
+        .map(|button| format!("Pad {button:?}"));
+    let names: Vec<_> = keys.chain(buttons).collect();
+
+    if names.is_empty() {
+        "-".to_string()
+    } else {
+        names.join(", ")
+    }
+}
@@ -1,6 +1,9 @@
 use super::{Cursor, InsightToolTip, UiNode, FONT_PATH};
 use crate::{
+    accessibility::Accessibility,
     character::{Character, ResponseResource, SelectedCharacter},
+    controls::{Action, Controls},
+    pixel_perfect::PixelCursor,
     type_writer::TypeWriter,
     CharacterSet,
 };
@@ -8,6 +11,7 @@ use bevy::{
     audio::Volume,
     input::{mouse::MouseButtonInput, ButtonState},
     prelude::*,
+    window::CursorMoved,
 };
 
 pub struct DecisionPlugin;
@@ -15,7 +19,14 @@ pub struct DecisionPlugin;
 impl Plugin for DecisionPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(PreUpdate, should_show_selection_ui.in_set(CharacterSet))
-            .add_systems(Update, selection_ui.in_set(CharacterSet))
+            .add_systems(
+                Update,
+                (point_at_decisions, selection_ui)
+                    .chain()
+                    .in_set(CharacterSet),
+            )
+            .insert_resource(DecisionFocus::default())
+            .insert_resource(DecisionPointer::default())
             .add_event::<Decision>();
     }
 }
@@ -38,7 +49,7 @@ impl From<&Decision> for DecisionType {
     }
 }
 
-#[derive(Debug, Component, Clone, Copy, Reflect)]
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Reflect)]
 pub enum DecisionType {
     Yes,
     No,
@@ -68,32 +79,110 @@ enum DecisionBox {
     No,
 }
 
+impl DecisionBox {
+    fn decision_type(&self) -> DecisionType {
+        match self {
+            Self::Yes => DecisionType::Yes,
+            Self::No => DecisionType::No,
+        }
+    }
+}
+
+/// The button inside a `ui/menu_box.png` frame, relative to the centre of the sprite.
+const DECISION_BOX_BOUNDS: Rect = Rect {
+    min: Vec2::new(-32., -11.5),
+    max: Vec2::new(32., 12.5),
+};
+
+/// The highlighted decision box, chosen with the mouse, keyboard or gamepad.
+#[derive(Debug, Default, Resource)]
+pub struct DecisionFocus(pub Option<DecisionType>);
+
+/// What the mouse is doing over the decision boxes.
+#[derive(Debug, Default, Resource)]
+struct DecisionPointer {
+    hovered: Option<DecisionType>,
+    /// The box the left button went down on, while it is held.
+    pressed: Option<DecisionType>,
+    /// Set for a frame when the button is released over the box it went down on.
+    clicked: Option<DecisionType>,
+}
+
+impl DecisionPointer {
+    fn is_held(&self, box_ty: DecisionType) -> bool {
+        self.pressed == Some(box_ty) && self.hovered == Some(box_ty)
+    }
+}
+
+/// Hit-tests the cursor against the decision box sprites in the pixel-perfect world.
+fn point_at_decisions(
+    cursor: PixelCursor,
+    decision_boxes: Query<(&DecisionBox, &GlobalTransform)>,
+    mut input: EventReader<MouseButtonInput>,
+    controls: Controls,
+    mut pointer: ResMut<DecisionPointer>,
+) {
+    pointer.hovered = cursor.world_position().and_then(|position| {
+        decision_boxes
+            .iter()
+            .find(|(_, transform)| {
+                let center = transform.translation().truncate();
+                Rect::from_corners(
+                    center + DECISION_BOX_BOUNDS.min,
+                    center + DECISION_BOX_BOUNDS.max,
+                )
+                .contains(position)
+            })
+            .map(|(box_ty, _)| box_ty.decision_type())
+    });
+
+    pointer.clicked = None;
+    for event in input.read() {
+        if event.button != MouseButton::Left || controls.is_locked() {
+            continue;
+        }
+        match event.state {
+            ButtonState::Pressed => pointer.pressed = pointer.hovered,
+            ButtonState::Released => {
+                if pointer.pressed == pointer.hovered {
+                    pointer.clicked = pointer.pressed;
+                }
+                pointer.pressed = None;
+            }
+        }
+    }
+}
+
 fn selection_ui(
     mut commands: Commands,
     server: Res<AssetServer>,
     mut writer: EventWriter<Decision>,
     selected_character: Query<&SelectedCharacter, With<ShowSelectionUi>>,
-    mut decision_boxes: Query<(&DecisionBox, &mut TextureAtlas)>,
+    mut decision_boxes: Query<(&DecisionBox, &mut TextureAtlas, &mut Sprite)>,
+    mut decision_text: Query<(&DecisionBox, &mut Text)>,
     mut decision_box_entities: Query<Entity, With<DecisionBox>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut tool_tip: Query<
         (Entity, &mut Style, &mut Visibility),
         (With<InsightToolTip>, Without<Cursor>),
     >,
-    windows: Query<&Window>,
-    mut input: EventReader<MouseButtonInput>,
+    mut pointer: ResMut<DecisionPointer>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    controls: Controls,
+    mut focus: ResMut<DecisionFocus>,
     response_res: Res<ResponseResource>,
+    accessibility: Res<Accessibility>,
 ) {
     let Ok(selected_character) = selected_character.get_single() else {
         for entity in decision_box_entities.iter() {
             commands.entity(entity).despawn();
         }
+        focus.0 = None;
+        *pointer = DecisionPointer::default();
 
         return;
     };
 
-    let window = windows.single();
-
     let (tool_tip_entity, mut tool_tip_style, mut visibility) = tool_tip.single_mut();
 
     if decision_boxes.is_empty() {
@@ -175,62 +264,77 @@ fn selection_ui(
         ));
     }
 
-    if let Some(mouse) = window.cursor_position() {
-        let did_click = input
-            .read()
-            .any(|i| i.state == ButtonState::Pressed && i.button == MouseButton::Left);
-
-        let click_sfx = "audio/stamp-102627.mp3";
-
-        let left = mouse.x / window.resolution.width() * 100.;
-        let top = mouse.y / window.resolution.height() * 100.;
-
-        if left > 6.5 && left < 30. && top > 13.12 && top < 26. {
-            if did_click {
-                //     commands.spawn(AudioBundle {
-                //         source: server.load(click_sfx),
-                //         settings: PlaybackSettings::DESPAWN
-                //             .with_volume(Volume::new(0.5))
-                //             .with_speed(1.8),
-                //     });
-                writer.send(Decision::No(selected_character.0.clone()));
-            }
+    let hovered = pointer.hovered;
 
-            *visibility = Visibility::Hidden;
+    if cursor_moved.read().count() > 0 {
+        focus.0 = hovered;
+    }
 
-            for (box_ty, mut atlas) in decision_boxes.iter_mut() {
-                match box_ty {
-                    DecisionBox::No => {
-                        atlas.index = 1;
-                    }
-                    _ => {}
-                }
-            }
-        } else if left > 69. && left < 93. && top > 13. && top < 26. {
-            if did_click {
-                // commands.spawn(AudioBundle {
-                //     source: server.load(click_sfx),
-                //     settings: PlaybackSettings::DESPAWN
-                //         .with_volume(Volume::new(0.5))
-                //         .with_speed(1.8),
-                // });
-                writer.send(Decision::Yes(selected_character.0.clone()));
-            }
+    if controls.just_pressed(Action::Left) {
+        focus.0 = Some(DecisionType::No);
+    } else if controls.just_pressed(Action::Right) {
+        focus.0 = Some(DecisionType::Yes);
+    }
 
-            *visibility = Visibility::Hidden;
+    let choice = if pointer.clicked.is_some() {
+        pointer.clicked
+    } else if controls.just_pressed(Action::Advance) {
+        focus.0
+    } else if controls.just_pressed(Action::Choose(0)) {
+        Some(DecisionType::No)
+    } else if controls.just_pressed(Action::Choose(1)) {
+        Some(DecisionType::Yes)
+    } else {
+        None
+    };
 
-            for (box_ty, mut atlas) in decision_boxes.iter_mut() {
-                match box_ty {
-                    DecisionBox::Yes => {
-                        atlas.index = 1;
-                    }
-                    _ => {}
-                }
-            }
+    match choice {
+        Some(DecisionType::Yes) => {
+            writer.send(Decision::Yes(selected_character.0.clone()));
+        }
+        Some(DecisionType::No) => {
+            writer.send(Decision::No(selected_character.0.clone()));
+        }
+        None => {}
+    }
+
+    if focus.0.is_some() {
+        *visibility = Visibility::Hidden;
+    }
+
+    let color = |box_ty: &DecisionBox, focused, unfocused| {
+        if !accessibility.high_contrast {
+            Color::WHITE
+        } else if focus.0 == Some(box_ty.decision_type()) {
+            focused
         } else {
-            for (_, mut atlas) in decision_boxes.iter_mut() {
-                atlas.index = 0;
-            }
+            unfocused
+        }
+    };
+
+    for (box_ty, mut atlas, mut sprite) in decision_boxes.iter_mut() {
+        let held = pointer.is_held(box_ty.decision_type());
+        atlas.index = if held || focus.0 == Some(box_ty.decision_type()) {
+            1
+        } else {
+            0
+        };
+        sprite.color = if held && !accessibility.high_contrast {
+            PRESSED_TINT
+        } else {
+            color(box_ty, Color::WHITE, HIGH_CONTRAST_DIM)
+        };
+    }
+
+    for (box_ty, mut text) in decision_text.iter_mut() {
+        let color = color(box_ty, HIGH_CONTRAST_FOCUS, HIGH_CONTRAST_DIM);
+        for section in text.sections.iter_mut() {
+            let alpha = section.style.color.alpha();
+            section.style.color = color.with_alpha(alpha);
         }
     }
 }
+
+const HIGH_CONTRAST_FOCUS: Color = Color::srgb(1., 0.85, 0.2);
+const HIGH_CONTRAST_DIM: Color = Color::srgb(0.3, 0.3, 0.3);
+const PRESSED_TINT: Color = Color::srgb(0.8, 0.8, 0.8);
@@ -3,18 +3,26 @@ use sickle_ui::prelude::*;
 
 use crate::{
     character::{Character, SelectedCharacter},
+    mixer::Bus,
     pixel_perfect::{HIGH_RES_LAYER, RES_HEIGHT, RES_WIDTH},
     state::KingdomState,
     CharacterSet, GameState,
 };
 
 use super::{AquireInsight, FONT_PATH};
+use std::time::Duration;
 
 pub struct InsightPlugin;
 
 impl Plugin for InsightPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Main), startup)
+            .add_systems(
+                Update,
+                follow_difficulty
+                    .before(super::update_cursor)
+                    .run_if(resource_changed::<KingdomState>),
+            )
             .add_systems(PostUpdate, aquire_insight.in_set(CharacterSet))
             .insert_resource(Insight::default());
     }
@@ -35,12 +43,11 @@ pub struct Insight {
     pub charge: f32,
 }
 
-pub const INSIGHT_CHARGE_TIME: f32 = 2.0;
-
 impl Default for Insight {
     fn default() -> Self {
         Self {
-            grace: Timer::from_seconds(INSIGHT_CHARGE_TIME, TimerMode::Repeating),
+            // Set from the difficulty once a reign starts.
+            grace: Timer::new(Duration::ZERO, TimerMode::Repeating),
             is_held: false,
             character: None,
             charge: 0.,
@@ -48,6 +55,13 @@ impl Default for Insight {
     }
 }
 
+fn follow_difficulty(state: Res<KingdomState>, mut insight: ResMut<Insight>) {
+    let charge_time = Duration::from_secs_f32(state.profile.insight_charge_time);
+    if insight.grace.duration() != charge_time {
+        insight.grace.set_duration(charge_time);
+    }
+}
+
 #[derive(Resource)]
 struct SpawnInsight(SystemId);
 
@@ -104,14 +118,16 @@ pub fn spawn_insight(
 
     let character = characters.get(insight.character.as_ref().unwrap()).unwrap();
     let request = character.request(state.day).unwrap();
+    // Wealth and happiness move by the difficulty's multiplier.
+    let scale = state.profile.delta_multiplier;
 
-    commands.spawn(AudioBundle {
-        source: server.load("audio/heartbeat.wav"),
-        settings: PlaybackSettings {
+    commands.spawn(Bus::Sfx.sound(
+        server.load("audio/heartbeat.wav"),
+        PlaybackSettings {
             mode: PlaybackMode::Despawn,
             ..Default::default()
         },
-    });
+    ));
 
     commands.spawn((
         InsightNode,
@@ -164,8 +180,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             " {}{}",
-                            get_leader(request.no.heart_size),
-                            request.no.heart_size.abs() as u32
+                            get_leader(request.no.heart_size * scale),
+                            (request.no.heart_size * scale).abs().round() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -193,8 +209,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             " {}{}",
-                            get_leader(request.no.happiness),
-                            request.no.happiness.abs() as u32
+                            get_leader(request.no.happiness * scale),
+                            (request.no.happiness * scale).abs() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -222,8 +238,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             " {}{}",
-                            get_leader(request.no.wealth),
-                            request.no.wealth.abs() as u32
+                            get_leader(request.no.wealth * scale),
+                            (request.no.wealth * scale).abs() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -250,8 +266,8 @@ pub fn spawn_insight(
                 .justify_content(JustifyContent::Start);
 
                 let prosp = KingdomState::calculate_prosperity(
-                    request.no.happiness.abs(),
-                    request.no.wealth.abs(),
+                    (request.no.happiness * scale).abs(),
+                    (request.no.wealth * scale).abs(),
                 );
 
                 row.spawn((
@@ -281,8 +297,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             "{}{} ",
-                            get_leader(request.yes.heart_size),
-                            request.yes.heart_size.abs() as u32
+                            get_leader(request.yes.heart_size * scale),
+                            (request.yes.heart_size * scale).abs().round() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -312,8 +328,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             "{}{} ",
-                            get_leader(request.yes.happiness),
-                            request.yes.happiness.abs() as u32
+                            get_leader(request.yes.happiness * scale),
+                            (request.yes.happiness * scale).abs() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -341,8 +357,8 @@ pub fn spawn_insight(
                     TextBundle::from_section(
                         &format!(
                             "{}{} ",
-                            get_leader(request.yes.wealth),
-                            request.yes.wealth.abs() as u32
+                            get_leader(request.yes.wealth * scale),
+                            (request.yes.wealth * scale).abs() as u32
                         ),
                         TextStyle {
                             font_size: 30.0,
@@ -365,8 +381,8 @@ pub fn spawn_insight(
             });
 
             let prosp = KingdomState::calculate_prosperity(
-                request.yes.happiness.abs(),
-                request.yes.wealth.abs(),
+                (request.yes.happiness * scale).abs(),
+                (request.yes.wealth * scale).abs(),
             );
 
             column.row(|row| {
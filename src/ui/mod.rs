@@ -1,24 +1,36 @@
+use crate::accessibility::Accessibility;
 use crate::animated_sprites::{AnimationIndices, AnimationTimer};
 use crate::character::{CharacterUi, SelectedCharacter};
-use crate::pixel_perfect::{HIGH_RES_LAYER, PIXEL_PERFECT_LAYER, RES_HEIGHT, RES_WIDTH};
-use crate::state::{KingdomState, NewHeartSize, MAX_HAPPINESS, MAX_HEART_SIZE, MAX_WEALTH};
+use crate::controls::{Action, Controls};
+use crate::mixer::Bus;
+use crate::pixel_perfect::{
+    PixelCursor, HIGH_RES_LAYER, PIXEL_PERFECT_LAYER, RES_HEIGHT, RES_WIDTH,
+};
+use crate::state::{KingdomState, NewHeartSize, MAX_HAPPINESS, MAX_WEALTH};
 use crate::time_state::TimeState;
+use crate::type_writer::TypeWriterText;
 use crate::{CharacterSet, GameState};
 use background::BackgroundPlugin;
 use bevy::input::mouse::MouseButtonInput;
 use bevy::input::ButtonState;
 use bevy::{audio::Volume, prelude::*};
 use bevy_tweening::*;
+use bindings::BindingsPlugin;
 use decision::{DecisionPlugin, ShowSelectionUi};
 use insight::{Insight, InsightPlugin};
 use lens::{SpriteColorLens, TransformRotateZLens, TransformScaleLens};
+use pause::PauseMenuPlugin;
 use serde::Deserialize;
+use settings::SettingsScreenPlugin;
 use sickle_ui::SickleUiPlugin;
 use std::time::Duration;
 
 pub mod background;
+pub mod bindings;
 pub mod decision;
 pub mod insight;
+pub mod pause;
+pub mod settings;
 
 pub struct UiPlugin;
 
@@ -30,6 +42,9 @@ impl Plugin for UiPlugin {
             InsightPlugin,
             DecisionPlugin,
             BackgroundPlugin,
+            BindingsPlugin,
+            SettingsScreenPlugin,
+            PauseMenuPlugin,
         ))
         .add_systems(
             OnEnter(GameState::Main),
@@ -45,10 +60,6 @@ impl Plugin for UiPlugin {
             Update,
             (heart_ui, mask_ui, display_state_bars).in_set(CharacterSet),
         )
-        .add_systems(
-            Update,
-            component_animator_system::<AudioSink>.in_set(AnimationSystem::AnimationUpdate),
-        )
         .add_event::<AquireInsight>();
     }
 }
@@ -100,6 +111,7 @@ fn startup(mut commands: Commands, mut state: ResMut<KingdomState>, server: Res<
         }),
         Name::new("Character Text"),
         CharacterUi::Request,
+        TypeWriterText::default(),
         UiNode,
     ));
 }
@@ -177,13 +189,14 @@ fn reset_cursor_state(
 }
 
 pub fn update_cursor(
-    mut windows: Query<&mut Window>,
+    pointer: PixelCursor,
     mut cursor: Query<(Entity, &mut Style), (With<Cursor>, Without<InsightToolTip>)>,
     mut tool_tip: Query<
         (Entity, &mut Style, &mut Visibility),
         (With<InsightToolTip>, Without<Cursor>),
     >,
     mut reader: EventReader<MouseButtonInput>,
+    controls: Controls,
     mut writer: EventWriter<AquireInsight>,
     mut insight: ResMut<Insight>,
     mut commands: Commands,
@@ -193,18 +206,17 @@ pub fn update_cursor(
     insight_sfx: Query<Entity, With<InsightChargeSfx>>,
     state: Res<KingdomState>,
     mut stat_bars: Query<&mut Visibility, (With<InsightStatBar>, Without<InsightToolTip>)>,
+    accessibility: Res<Accessibility>,
 ) {
-    let mut window = windows.single_mut();
-    window.cursor.visible = false;
-
     let Ok((entity, mut style)) = cursor.get_single_mut() else {
         return;
     };
     let (tool_tip_entity, mut tool_tip_style, mut tool_tip_vis) = tool_tip.single_mut();
 
-    if let Some(world_position) = window.physical_cursor_position() {
-        let left = world_position.x / window.resolution.width() * 100.;
-        let top = world_position.y / window.resolution.height() * 100.;
+    let mut in_zone = false;
+    if let Some(fraction) = pointer.canvas_fraction() {
+        let left = fraction.x * 100.;
+        let top = fraction.y * 100.;
 
         style.left = Val::Percent(left - 50.);
         style.top = Val::Percent(top - 50.);
@@ -212,77 +224,95 @@ pub fn update_cursor(
         tool_tip_style.left = Val::Percent(left + 5.);
         tool_tip_style.top = Val::Percent(top - 50. + 5.);
 
-        if top < 60. && !selected_character.is_empty() {
-            commands.entity(entity).insert(CursorCanDecide);
-            insight.grace.tick(time.delta());
+        in_zone = top < 60.;
+    }
 
-            if insight.is_held {
-                insight.charge = insight.grace.remaining().as_secs_f32()
-                    / insight.grace.duration().as_secs_f32();
+    let toggled = accessibility.toggle_insight && insight.is_held;
+    if (in_zone || toggled || controls.pressed(Action::Insight)) && !selected_character.is_empty() {
+        commands.entity(entity).insert(CursorCanDecide);
+        insight.grace.tick(time.delta());
+
+        if insight.is_held {
+            insight.charge =
+                insight.grace.remaining().as_secs_f32() / insight.grace.duration().as_secs_f32();
+        } else {
+            if insight.character.as_ref() == selected_character.iter().next().map(|s| &s.1 .0) {
+                insight.charge = 0.0;
             } else {
-                if insight.character.as_ref() == selected_character.iter().next().map(|s| &s.1 .0) {
-                    insight.charge = 0.0;
-                } else {
-                    insight.charge = 1.0;
-                }
+                insight.charge = 1.0;
             }
+        }
 
-            for input in reader.read() {
-                if input.button == MouseButton::Right || input.button == MouseButton::Left {
-                    match input.state {
-                        ButtonState::Pressed => {
-                            if insight.is_held == false
-                                && insight.character.as_ref()
-                                    != selected_character.iter().next().map(|s| &s.1 .0)
-                                && state.day > 0
-                            {
-                                let sfx_path = "audio/sci-fi-sound-effect-designed-circuits-sfx-tonal-15-202059.mp3";
-                                commands.spawn((
-                                    AudioBundle {
-                                        source: server.load(sfx_path),
-                                        settings: PlaybackSettings::default()
-                                            .with_volume(Volume::new(0.4)),
-                                    },
-                                    InsightChargeSfx,
-                                ));
-                            }
-
-                            insight.is_held = true;
-                        }
-                        ButtonState::Released => {
-                            reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
-                        }
+        let mut presses: Vec<ButtonState> = reader
+            .read()
+            .filter(|input| input.button == MouseButton::Right || input.button == MouseButton::Left)
+            .filter(|_| !controls.is_locked())
+            .map(|input| input.state)
+            .collect();
+        if controls.just_pressed(Action::Insight) {
+            presses.push(ButtonState::Pressed);
+        }
+        if controls.just_released(Action::Insight) {
+            presses.push(ButtonState::Released);
+        }
+
+        for press in presses {
+            match press {
+                ButtonState::Pressed if accessibility.toggle_insight && insight.is_held => {
+                    reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
+                }
+                ButtonState::Released if accessibility.toggle_insight => continue,
+                ButtonState::Pressed => {
+                    if insight.is_held == false
+                        && insight.character.as_ref()
+                            != selected_character.iter().next().map(|s| &s.1 .0)
+                        && state.day > 0
+                    {
+                        let sfx_path =
+                            "audio/sci-fi-sound-effect-designed-circuits-sfx-tonal-15-202059.mp3";
+                        commands.spawn((
+                            Bus::Sfx.sound(
+                                server.load(sfx_path),
+                                PlaybackSettings::default().with_volume(Volume::new(0.4)),
+                            ),
+                            InsightChargeSfx,
+                        ));
                     }
 
-                    insight.grace.reset();
+                    insight.is_held = true;
+                }
+                ButtonState::Released => {
+                    reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
                 }
             }
 
-            if insight.grace.finished() && insight.is_held && state.day > 0 {
-                writer.send(AquireInsight);
-                reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
-            }
+            insight.grace.reset();
+        }
 
-            for mut vis in stat_bars.iter_mut() {
-                if state.day > 0 {
-                    *vis = Visibility::Visible;
-                } else {
-                    *vis = Visibility::Hidden;
-                }
-            }
+        if insight.grace.finished() && insight.is_held && state.day > 0 {
+            writer.send(AquireInsight);
+            reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
+        }
 
-            if insight.character.as_ref() != selected_character.iter().next().map(|s| &s.1 .0) {
-                if !selected_character.is_empty() && state.day > 0 {
-                    *tool_tip_vis = Visibility::Visible;
-                }
+        for mut vis in stat_bars.iter_mut() {
+            if state.day > 0 {
+                *vis = Visibility::Visible;
             } else {
-                *tool_tip_vis = Visibility::Hidden;
+                *vis = Visibility::Hidden;
             }
-        } else {
-            reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
+        }
 
+        if insight.character.as_ref() != selected_character.iter().next().map(|s| &s.1 .0) {
+            if !selected_character.is_empty() && state.day > 0 {
+                *tool_tip_vis = Visibility::Visible;
+            }
+        } else {
             *tool_tip_vis = Visibility::Hidden;
         }
+    } else {
+        reset_cursor_state(&mut insight, &mut commands, &insight_sfx);
+
+        *tool_tip_vis = Visibility::Hidden;
     }
 }
 
@@ -468,7 +498,9 @@ fn display_state_bars(
             if filler {
                 let new_scale = match bar {
                     StatBar::Wealth => (state.wealth / MAX_WEALTH * 0.5).clamp(0., 0.5),
-                    StatBar::Heart => (state.heart_size / MAX_HEART_SIZE * 0.5).clamp(0., 0.5),
+                    StatBar::Heart => {
+                        (state.profile.heart_fraction(state.heart_size) * 0.5).clamp(0., 0.5)
+                    }
                     StatBar::Happiness => (state.happiness / MAX_HAPPINESS * 0.5).clamp(0., 0.5),
                     StatBar::Insight => ((1.0 - insight.charge) * 0.5).clamp(0., 0.5),
                 };
@@ -481,7 +513,9 @@ fn display_state_bars(
     }
 }
 
-#[derive(Debug, Default, Deserialize, Asset, Component, Reflect, Clone, PartialEq, Eq, Copy)]
+#[derive(
+    Debug, Default, Deserialize, Asset, Component, Reflect, Clone, PartialEq, Eq, Hash, Copy,
+)]
 pub enum Mask {
     Happy,
     #[default]
@@ -490,6 +524,18 @@ pub enum Mask {
     None,
 }
 
+impl Mask {
+    /// Suffix of the emotion-specific sprites for this mask, e.g. `_head_happy.png`.
+    pub fn sprite_suffix(&self) -> Option<&'static str> {
+        match self {
+            Self::Happy => Some("happy"),
+            Self::Neutral => Some("neutral"),
+            Self::Sad => Some("sad"),
+            Self::None => None,
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct ActiveMask(pub Mask);
 
@@ -604,7 +650,12 @@ fn heart_ui(
     mut heart_flash: Query<&mut Visibility, With<HeartFlash>>,
 ) {
     if let Ok(mut vis) = heart_flash.get_single_mut() {
-        if state.heart_size == 5. || state.heart_size == 1. {
+        // One step from either bound.
+        let profile = &state.profile;
+        let step = profile.delta_multiplier;
+        if state.heart_size >= profile.max_heart_size - step
+            || state.heart_size <= profile.min_heart_size + step
+        {
             *vis = Visibility::Visible;
         } else {
             *vis = Visibility::Hidden;
@@ -613,12 +664,12 @@ fn heart_ui(
 
     if let Ok((entity, mut transform)) = heart.get_single_mut() {
         for new_size in reader.read() {
-            transform.scale = Vec3::splat(HEART_SCALE * (new_size.0 / MAX_HEART_SIZE));
+            transform.scale = Vec3::splat(HEART_SCALE * state.profile.heart_fraction(new_size.0));
 
-            commands.spawn(AudioBundle {
-                source: server.load("audio/heartbeat.wav"),
-                settings: PlaybackSettings::DESPAWN,
-            });
+            commands.spawn(Bus::Sfx.sound(
+                server.load("audio/heartbeat.wav"),
+                PlaybackSettings::DESPAWN,
+            ));
 
             let pulse = Tween::new(
                 // Use a quadratic easing on both endpoints.
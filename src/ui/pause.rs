@@ -0,0 +1,243 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*, window::WindowFocused};
+use sickle_ui::prelude::*;
+
+use crate::{
+    controls::{Action, Controls, LocksControls},
+    end::reset_game,
+    time_state::restart_day,
+    GameState,
+};
+
+use super::{settings::OpenSettingsScreen, FONT_PATH};
+
+/// An overlay that holds the game still while it is open. Pause opens and closes it, and it
+/// opens by itself when the window loses focus.
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PauseCursor::default()).add_systems(
+            Update,
+            (
+                (toggle_pause_menu, navigate_pause_menu, update_pause_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Main)),
+                hold_time,
+            )
+                .chain(),
+        );
+    }
+}
+
+const FONT_SIZE: f32 = 30.;
+const TEXT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FOCUS_COLOR: Color = Color::WHITE;
+
+#[derive(Component)]
+struct PauseMenu;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum PauseRow {
+    Resume,
+    Settings,
+    RestartDay,
+    MainMenu,
+    Quit,
+}
+
+const ROWS: [PauseRow; 5] = [
+    PauseRow::Resume,
+    PauseRow::Settings,
+    PauseRow::RestartDay,
+    PauseRow::MainMenu,
+    PauseRow::Quit,
+];
+
+impl PauseRow {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Resume => "Resume",
+            Self::Settings => "Settings",
+            Self::RestartDay => "Restart day",
+            Self::MainMenu => "Main menu",
+            Self::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+struct PauseCursor {
+    row: usize,
+    /// Whether another overlay was open over the menu this frame or the last. Pause closes
+    /// those overlays too, so the menu ignores it until a frame after they are gone.
+    covered: bool,
+}
+
+fn open_pause_menu(commands: &mut Commands, server: &AssetServer) {
+    let style = |color| TextStyle {
+        font_size: FONT_SIZE,
+        font: server.load(FONT_PATH),
+        color,
+    };
+
+    commands.ui_builder(UiRoot).container(
+        (
+            PauseMenu,
+            LocksControls,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0., 0., 0., 0.9).into(),
+                // Below the settings and bindings screens it opens.
+                z_index: ZIndex::Global(900),
+                ..Default::default()
+            },
+        ),
+        |menu| {
+            menu.spawn(TextBundle::from_section("Paused\n", style(FOCUS_COLOR)));
+
+            for row in ROWS {
+                menu.spawn((
+                    row,
+                    Interaction::default(),
+                    TextBundle::from_section(row.label(), style(TEXT_COLOR)),
+                ));
+            }
+        },
+    );
+}
+
+fn toggle_pause_menu(
+    mut commands: Commands,
+    controls: Controls,
+    mut cursor: ResMut<PauseCursor>,
+    mut focus: EventReader<WindowFocused>,
+    menu: Query<Entity, With<PauseMenu>>,
+    overlays: Query<(), With<LocksControls>>,
+    server: Res<AssetServer>,
+) {
+    let lost_focus = focus.read().any(|event| !event.focused);
+
+    if let Ok(entity) = menu.get_single() {
+        let covered = overlays.iter().count() > 1;
+        let was_covered = cursor.covered;
+        if covered != was_covered {
+            cursor.covered = covered;
+        }
+
+        if !covered && !was_covered && controls.menu_just_pressed(Action::Pause) {
+            commands.entity(entity).despawn_recursive();
+        }
+    } else if controls.just_pressed(Action::Pause) || lost_focus {
+        *cursor = PauseCursor::default();
+        open_pause_menu(&mut commands, &server);
+    }
+}
+
+fn navigate_pause_menu(
+    mut commands: Commands,
+    mut cursor: ResMut<PauseCursor>,
+    controls: Controls,
+    rows: Query<(&PauseRow, &Interaction), Changed<Interaction>>,
+    menu: Query<Entity, With<PauseMenu>>,
+    mut open_settings: EventWriter<OpenSettingsScreen>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let Ok(menu) = menu.get_single() else {
+        return;
+    };
+
+    if cursor.covered {
+        return;
+    }
+
+    let last = ROWS.len() - 1;
+    let mut activate = controls.menu_just_pressed(Action::Advance);
+
+    if controls.menu_just_pressed(Action::Up) {
+        cursor.row = cursor.row.checked_sub(1).unwrap_or(last);
+    } else if controls.menu_just_pressed(Action::Down) {
+        cursor.row = if cursor.row >= last {
+            0
+        } else {
+            cursor.row + 1
+        };
+    }
+
+    for (row, interaction) in rows.iter() {
+        let index = ROWS.iter().position(|r| r == row).unwrap_or_default();
+        match interaction {
+            Interaction::Hovered => cursor.row = index,
+            Interaction::Pressed => {
+                cursor.row = index;
+                activate = true;
+            }
+            Interaction::None => {}
+        }
+    }
+
+    if !activate {
+        return;
+    }
+
+    match ROWS[cursor.row] {
+        PauseRow::Resume => {
+            commands.entity(menu).despawn_recursive();
+        }
+        PauseRow::Settings => {
+            open_settings.send(OpenSettingsScreen);
+        }
+        PauseRow::RestartDay => {
+            commands.entity(menu).despawn_recursive();
+            commands.add(|world: &mut World| world.run_system_once(restart_day));
+        }
+        PauseRow::MainMenu => {
+            commands.entity(menu).despawn_recursive();
+            commands.add(|world: &mut World| world.run_system_once(reset_game));
+        }
+        PauseRow::Quit => {
+            exit.send(AppExit::Success);
+        }
+    }
+}
+
+fn update_pause_rows(
+    cursor: Res<PauseCursor>,
+    mut rows: Query<(&PauseRow, &mut Text)>,
+    added: Query<(), Added<PauseRow>>,
+) {
+    if !cursor.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let focused = ROWS[cursor.row];
+    for (row, mut text) in rows.iter_mut() {
+        text.sections[0].style.color = if *row == focused {
+            FOCUS_COLOR
+        } else {
+            TEXT_COLOR
+        };
+    }
+}
+
+/// Virtual time, and with it tweens, the typewriter and every sound, stands still for as
+/// long as the menu is open.
+fn hold_time(menu: Query<(), With<PauseMenu>>, mut time: ResMut<Time<Virtual>>) {
+    let open = !menu.is_empty();
+    if open == time.is_paused() {
+        return;
+    }
+
+    if open {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+}
@@ -0,0 +1,541 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::WindowMode};
+use sickle_ui::prelude::*;
+
+use crate::{
+    accessibility::{Accessibility, TEXT_SCALES},
+    config,
+    controls::{Action, Controls, LocksControls},
+    mixer::Bus,
+    pixel_perfect::{CanvasScale, CANVAS_SCALES},
+    settings::{Settings, RESOLUTIONS, WINDOW_MODES},
+};
+
+use super::{bindings::OpenBindingsScreen, FONT_PATH};
+
+/// An overlay with the player's options. Up and down move between rows, left and right
+/// change the focused option and advance toggles it, or mutes a volume.
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SettingsCursor::default())
+            .add_event::<OpenSettingsScreen>()
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_screen,
+                    navigate_settings,
+                    update_settings_rows,
+                )
+                    .chain(),
+            );
+    }
+}
+
+const FONT_SIZE: f32 = 30.;
+const TEXT_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FOCUS_COLOR: Color = Color::WHITE;
+
+const SLIDER_STEPS: usize = 10;
+const SLIDER_KNOB: &str = "ui/Slider/x2/Slider_components1.png";
+const SLIDER_KNOB_FOCUSED: &str = "ui/Slider/x2/Slider_components2.png";
+const SLIDER_FILLED: &str = "ui/Slider/x2/Slider_components3.png";
+const SLIDER_EMPTY: &str = "ui/Slider/x2/Slider_components4.png";
+
+/// Opens the settings screen from another menu.
+#[derive(Event)]
+pub struct OpenSettingsScreen;
+
+#[derive(Component)]
+struct SettingsScreen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum SettingsRow {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    VoiceVolume,
+    AmbienceVolume,
+    WindowMode,
+    Resolution,
+    CanvasScale,
+    Vsync,
+    InstantText,
+    ToggleInsight,
+    ReducedMotion,
+    TextScale,
+    HighContrast,
+    Controls,
+}
+
+const ROWS: [SettingsRow; 15] = [
+    SettingsRow::MasterVolume,
+    SettingsRow::MusicVolume,
+    SettingsRow::SfxVolume,
+    SettingsRow::VoiceVolume,
+    SettingsRow::AmbienceVolume,
+    SettingsRow::WindowMode,
+    SettingsRow::Resolution,
+    SettingsRow::CanvasScale,
+    SettingsRow::Vsync,
+    SettingsRow::InstantText,
+    SettingsRow::ToggleInsight,
+    SettingsRow::ReducedMotion,
+    SettingsRow::TextScale,
+    SettingsRow::HighContrast,
+    SettingsRow::Controls,
+];
+
+/// The label or value text of a row.
+#[derive(Component)]
+struct RowText {
+    row: SettingsRow,
+    is_value: bool,
+}
+
+/// One notch of a volume slider. Clicking it sets the volume to `step / SLIDER_STEPS`.
+#[derive(Component)]
+struct SliderTile {
+    row: SettingsRow,
+    step: usize,
+}
+
+#[derive(SystemParam)]
+struct Options<'w> {
+    settings: ResMut<'w, Settings>,
+    accessibility: ResMut<'w, Accessibility>,
+}
+
+impl Options<'_> {
+    fn is_changed(&self) -> bool {
+        self.settings.is_changed() || self.accessibility.is_changed()
+    }
+
+    /// Mutes the buses of a volume row, or unmutes them if they all are already.
+    fn toggle_mute(&mut self, row: SettingsRow) {
+        let muted = row.buses().iter().all(|bus| self.settings.is_muted(*bus));
+        for bus in row.buses() {
+            *self.settings.muted_mut(*bus) = !muted;
+        }
+        self.save(row);
+    }
+
+    fn save(&self, row: SettingsRow) {
+        if row.is_accessibility() {
+            config::save(Accessibility::FILE, &*self.accessibility);
+        } else {
+            config::save(Settings::FILE, &*self.settings);
+        }
+    }
+}
+
+/// Steps through `options` from `current`, wrapping at either end.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: i32) -> T {
+    let index = options.iter().position(|o| *o == current).unwrap_or(0) as i32;
+    options[(index + step).rem_euclid(options.len() as i32) as usize]
+}
+
+impl SettingsRow {
+    fn header(&self) -> Option<&'static str> {
+        match self {
+            Self::MasterVolume => Some("Audio"),
+            Self::WindowMode => Some("Video"),
+            Self::InstantText => Some("Accessibility"),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::MasterVolume => "Master",
+            Self::MusicVolume => "Music",
+            Self::SfxVolume => "Effects",
+            Self::VoiceVolume => "Voices",
+            Self::AmbienceVolume => "Ambience",
+            Self::WindowMode => "Window",
+            Self::Resolution => "Resolution",
+            Self::CanvasScale => "Scaling",
+            Self::Vsync => "VSync",
+            Self::InstantText => "Instant text",
+            Self::ToggleInsight => "Toggle insight",
+            Self::ReducedMotion => "Reduced motion",
+            Self::TextScale => "Text size",
+            Self::HighContrast => "High contrast",
+            Self::Controls => "Controls...",
+        }
+    }
+
+    fn is_accessibility(&self) -> bool {
+        matches!(
+            self,
+            Self::InstantText
+                | Self::ToggleInsight
+                | Self::ReducedMotion
+                | Self::TextScale
+                | Self::HighContrast
+        )
+    }
+
+    fn volume(&self, settings: &Settings) -> Option<f32> {
+        match self {
+            Self::MasterVolume => Some(settings.master_volume),
+            Self::MusicVolume => Some(settings.music_volume),
+            Self::SfxVolume => Some(settings.sfx_volume),
+            Self::VoiceVolume => Some(settings.voice_volume),
+            Self::AmbienceVolume => Some(settings.ambience_volume),
+            _ => None,
+        }
+    }
+
+    fn volume_mut<'a>(&self, settings: &'a mut Settings) -> Option<&'a mut f32> {
+        match self {
+            Self::MasterVolume => Some(&mut settings.master_volume),
+            Self::MusicVolume => Some(&mut settings.music_volume),
+            Self::SfxVolume => Some(&mut settings.sfx_volume),
+            Self::VoiceVolume => Some(&mut settings.voice_volume),
+            Self::AmbienceVolume => Some(&mut settings.ambience_volume),
+            _ => None,
+        }
+    }
+
+    fn is_slider(&self) -> bool {
+        self.volume(&Settings::default()).is_some()
+    }
+
+    /// The buses a volume row mutes. The master row mutes all of them.
+    fn buses(&self) -> &'static [Bus] {
+        match self {
+            Self::MasterVolume => &Bus::ALL,
+            Self::MusicVolume => &[Bus::Music],
+            Self::SfxVolume => &[Bus::Sfx],
+            Self::VoiceVolume => &[Bus::Voice],
+            Self::AmbienceVolume => &[Bus::Ambience],
+            _ => &[],
+        }
+    }
+
+    fn value(&self, options: &Options) -> String {
+        let (settings, accessibility) = (&options.settings, &options.accessibility);
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+
+        if let Some(volume) = self.volume(settings) {
+            return if self.buses().iter().all(|bus| settings.is_muted(*bus)) {
+                "Muted".to_string()
+            } else {
+                format!("{:.0}%", volume * 100.)
+            };
+        }
+
+        match self {
+            Self::WindowMode => match settings.window_mode {
+                WindowMode::Windowed => "Windowed",
+                WindowMode::BorderlessFullscreen => "Borderless",
+                _ => "Fullscreen",
+            }
+            .to_string(),
+            Self::Resolution => format!("{}x{}", settings.resolution.0, settings.resolution.1),
+            Self::CanvasScale => match settings.canvas_scale {
+                CanvasScale::Integer => "Pixel perfect",
+                CanvasScale::Fit => "Fit",
+                CanvasScale::Stretch => "Stretch",
+            }
+            .to_string(),
+            Self::Vsync => on_off(settings.vsync),
+            Self::InstantText => on_off(accessibility.instant_text),
+            Self::ToggleInsight => on_off(accessibility.toggle_insight),
+            Self::ReducedMotion => on_off(accessibility.reduced_motion),
+            Self::TextScale => format!("{:.0}%", accessibility.text_scale * 100.),
+            Self::HighContrast => on_off(accessibility.high_contrast),
+            _ => String::new(),
+        }
+    }
+
+    /// Steps the option by `step`, returning whether anything changed.
+    fn change(&self, options: &mut Options, step: i32) -> bool {
+        if let Some(volume) = self.volume_mut(&mut options.settings) {
+            let notch = (*volume * SLIDER_STEPS as f32).round() as i32 + step;
+            *volume = notch.clamp(0, SLIDER_STEPS as i32) as f32 / SLIDER_STEPS as f32;
+
+            return true;
+        }
+
+        let settings = &mut options.settings;
+        let accessibility = &mut options.accessibility;
+        match self {
+            Self::WindowMode => {
+                settings.window_mode = cycle(&WINDOW_MODES, settings.window_mode, step)
+            }
+            Self::Resolution => {
+                settings.resolution = cycle(&RESOLUTIONS, settings.resolution, step)
+            }
+            Self::CanvasScale => {
+                settings.canvas_scale = cycle(&CANVAS_SCALES, settings.canvas_scale, step)
+            }
+            Self::Vsync => settings.vsync ^= true,
+            Self::InstantText => accessibility.instant_text ^= true,
+            Self::ToggleInsight => accessibility.toggle_insight ^= true,
+            Self::ReducedMotion => accessibility.reduced_motion ^= true,
+            Self::HighContrast => accessibility.high_contrast ^= true,
+            Self::TextScale => {
+                accessibility.text_scale = cycle(&TEXT_SCALES, accessibility.text_scale, step)
+            }
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+#[derive(Default, Resource)]
+struct SettingsCursor(usize);
+
+fn open_settings_screen(commands: &mut Commands, server: &AssetServer) {
+    let style = |color| TextStyle {
+        font_size: FONT_SIZE,
+        font: server.load(FONT_PATH),
+        color,
+    };
+
+    commands.ui_builder(UiRoot).container(
+        (
+            SettingsScreen,
+            LocksControls,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.),
+                    ..Default::default()
+                },
+                background_color: Color::srgba(0., 0., 0., 0.9).into(),
+                z_index: ZIndex::Global(1000),
+                ..Default::default()
+            },
+        ),
+        |screen| {
+            screen.spawn(TextBundle::from_section("Settings", style(FOCUS_COLOR)));
+
+            for row in ROWS {
+                if let Some(header) = row.header() {
+                    screen.spawn(TextBundle::from_section(
+                        format!("\n{header}"),
+                        style(FOCUS_COLOR),
+                    ));
+                }
+
+                screen.container(
+                    (
+                        row,
+                        Interaction::default(),
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Px(700.),
+                                justify_content: JustifyContent::SpaceBetween,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                    ),
+                    |container| {
+                        container.spawn((
+                            RowText {
+                                row,
+                                is_value: false,
+                            },
+                            TextBundle::from_section(row.label(), style(TEXT_COLOR)),
+                        ));
+
+                        let value = (
+                            RowText {
+                                row,
+                                is_value: true,
+                            },
+                            TextBundle::from_section("", style(TEXT_COLOR)),
+                        );
+
+                        if !row.is_slider() {
+                            container.spawn(value);
+
+                            return;
+                        }
+
+                        container.container(NodeBundle::default(), |slider| {
+                            for step in 0..=SLIDER_STEPS {
+                                slider.spawn((
+                                    SliderTile { row, step },
+                                    Interaction::default(),
+                                    ImageBundle {
+                                        image: UiImage::new(server.load(SLIDER_EMPTY)),
+                                        style: Style {
+                                            width: Val::Px(18.),
+                                            height: Val::Px(32.),
+                                            ..Default::default()
+                                        },
+                                        ..Default::default()
+                                    },
+                                ));
+                            }
+
+                            slider.spawn(value).style().min_width(Val::Px(120.));
+                        });
+                    },
+                );
+            }
+        },
+    );
+}
+
+fn toggle_settings_screen(
+    mut commands: Commands,
+    controls: Controls,
+    mut cursor: ResMut<SettingsCursor>,
+    screen: Query<Entity, With<SettingsScreen>>,
+    mut open: EventReader<OpenSettingsScreen>,
+    server: Res<AssetServer>,
+) {
+    let opened_elsewhere = open.read().count() > 0;
+
+    if let Ok(entity) = screen.get_single() {
+        if controls.menu_just_pressed(Action::OpenSettings)
+            || controls.menu_just_pressed(Action::Pause)
+        {
+            commands.entity(entity).despawn_recursive();
+        }
+    } else if controls.just_pressed(Action::OpenSettings) || opened_elsewhere {
+        *cursor = SettingsCursor::default();
+        open_settings_screen(&mut commands, &server);
+    }
+}
+
+/// Either a whole row or one notch of its slider.
+type RowOrTile<'a> = (Option<&'a SettingsRow>, Option<&'a SliderTile>);
+
+fn navigate_settings(
+    mut commands: Commands,
+    mut cursor: ResMut<SettingsCursor>,
+    mut options: Options,
+    controls: Controls,
+    interactions: Query<(&Interaction, RowOrTile), Changed<Interaction>>,
+    screen: Query<(Entity, Ref<SettingsScreen>)>,
+    mut open_bindings: EventWriter<OpenBindingsScreen>,
+) {
+    let Ok((screen, opened)) = screen.get_single() else {
+        return;
+    };
+
+    // The press that opened the screen from another menu is not meant for it.
+    if opened.is_added() {
+        return;
+    }
+
+    let last = ROWS.len() - 1;
+    let mut activate = controls.menu_just_pressed(Action::Advance);
+
+    if controls.menu_just_pressed(Action::Up) {
+        cursor.0 = cursor.0.checked_sub(1).unwrap_or(last);
+    } else if controls.menu_just_pressed(Action::Down) {
+        cursor.0 = if cursor.0 >= last { 0 } else { cursor.0 + 1 };
+    }
+
+    let mut slider_step = None;
+    for (interaction, (row, tile)) in interactions.iter() {
+        if let Some(row) = row {
+            let index = ROWS.iter().position(|r| r == row).unwrap_or_default();
+            match interaction {
+                Interaction::Hovered => cursor.0 = index,
+                Interaction::Pressed => {
+                    cursor.0 = index;
+                    activate = true;
+                }
+                Interaction::None => {}
+            }
+        }
+
+        if let (Some(tile), Interaction::Pressed) = (tile, interaction) {
+            slider_step = Some(tile);
+        }
+    }
+
+    if let Some(tile) = slider_step {
+        if let Some(volume) = tile.row.volume_mut(&mut options.settings) {
+            *volume = tile.step as f32 / SLIDER_STEPS as f32;
+            options.save(tile.row);
+        }
+
+        return;
+    }
+
+    let row = ROWS[cursor.0];
+    if activate && row.is_slider() {
+        options.toggle_mute(row);
+
+        return;
+    }
+
+    let step = if controls.menu_just_pressed(Action::Left) {
+        -1
+    } else if controls.menu_just_pressed(Action::Right) || activate {
+        1
+    } else {
+        return;
+    };
+
+    if row == SettingsRow::Controls {
+        if activate {
+            commands.entity(screen).despawn_recursive();
+            open_bindings.send(OpenBindingsScreen);
+        }
+    } else if row.change(&mut options, step) {
+        options.save(row);
+    }
+}
+
+fn update_settings_rows(
+    cursor: Res<SettingsCursor>,
+    options: Options,
+    mut texts: Query<(&RowText, &mut Text)>,
+    mut tiles: Query<(&SliderTile, &mut UiImage)>,
+    added: Query<(), Added<SettingsRow>>,
+    server: Res<AssetServer>,
+) {
+    if !cursor.is_changed() && !options.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let focused = ROWS[cursor.0];
+
+    for (row_text, mut text) in texts.iter_mut() {
+        let color = if row_text.row == focused {
+            FOCUS_COLOR
+        } else {
+            TEXT_COLOR
+        };
+
+        if row_text.is_value {
+            text.sections[0].value = row_text.row.value(&options);
+        }
+        text.sections[0].style.color = color;
+    }
+
+    for (tile, mut image) in tiles.iter_mut() {
+        let Some(volume) = tile.row.volume(&options.settings) else {
+            continue;
+        };
+
+        let notch = (volume * SLIDER_STEPS as f32).round() as usize;
+        let path = if tile.step < notch {
+            SLIDER_FILLED
+        } else if tile.step > notch {
+            SLIDER_EMPTY
+        } else if tile.row == focused {
+            SLIDER_KNOB_FOCUSED
+        } else {
+            SLIDER_KNOB
+        };
+        image.texture = server.load(path);
+    }
+}
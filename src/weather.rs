@@ -0,0 +1,216 @@
+use crate::{
+    ambience::{AmbienceEvent, AmbienceLoop},
+    menu::ParallaxSprite,
+    particles::Particles,
+    pixel_perfect::CanvasGrade,
+    scenery::{SceneryKind, SceneryPace, ShowScenery},
+    state::{KingdomState, PrinceState, Season},
+    ui::{background::BackgroundParticles, hex_to_vec4},
+    GameState,
+};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use bevy_common_assets::yaml::YamlAssetPlugin;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// How long the weather's loops take to crossfade.
+const WEATHER_FADE_SECS: f32 = 5.;
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(YamlAssetPlugin::<WeatherManifest>::new(&["weather.yaml"]))
+            .init_resource::<WeatherMood>()
+            .add_systems(OnEnter(GameState::MainMenu), clear_weather)
+            .add_systems(
+                Update,
+                (
+                    follow_scenery,
+                    apply_weather_mood.run_if(resource_changed::<WeatherMood>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The weather outside the court, and which one each season gets.
+///
+/// ```yaml
+/// forecast:
+///   - { season: winter, flag: drought-ignored, weather: fog }
+///   - { season: winter, weather: snow }
+/// weathers:
+///   snow:
+///     particles: snow
+///     grade: 0xe6ecff
+///     crowd_pace: 0.6
+///     ambience:
+///       - { path: audio/wind.mp3, volume: 1.0 }
+/// ```
+#[derive(Debug, Deserialize, Asset, TypePath)]
+pub struct WeatherManifest {
+    /// Checked in order; the first matching rule is used.
+    pub forecast: Vec<ForecastRule>,
+    pub weathers: HashMap<WeatherKind, Weather>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForecastRule {
+    pub season: Season,
+    #[serde(default)]
+    pub flag: Option<StoryFlag>,
+    pub weather: WeatherKind,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Weather {
+    /// A preset in the particle manifest.
+    #[serde(default)]
+    pub particles: Option<String>,
+    /// An `0xRRGGBB` tint for the pixel canvas.
+    #[serde(default = "grade_default")]
+    pub grade: u32,
+    /// How fast the crowd layers animate.
+    #[serde(default = "crowd_pace_default")]
+    pub crowd_pace: f32,
+    #[serde(default)]
+    pub ambience: Vec<AmbienceLoop>,
+}
+
+fn grade_default() -> u32 {
+    0xffffff
+}
+
+fn crowd_pace_default() -> f32 {
+    1.
+}
+
+/// A decision earlier in the reign that can change the weather.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StoryFlag {
+    /// The king shared the royal reserves after the prince's drought request.
+    DroughtRelieved,
+    /// The king refused the prince's drought request.
+    DroughtIgnored,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct WeatherAssets {
+    #[asset(path = "weather/court.weather.yaml")]
+    manifest: Handle<WeatherManifest>,
+}
+
+/// The id of a weather in the [`WeatherManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct WeatherKind(Cow<'static, str>);
+
+/// Looks up the weather for the current season and story flags.
+#[derive(SystemParam)]
+struct Forecast<'w> {
+    weather_assets: Res<'w, WeatherAssets>,
+    manifests: Res<'w, Assets<WeatherManifest>>,
+    state: Res<'w, KingdomState>,
+    prince: Res<'w, PrinceState>,
+}
+
+impl Forecast<'_> {
+    fn weather(&self) -> Option<&Weather> {
+        let manifest = self.manifests.get(&self.weather_assets.manifest)?;
+        let season = self.state.season();
+
+        let rule = manifest
+            .forecast
+            .iter()
+            .find(|rule| rule.season == season && rule.flag.is_none_or(|flag| self.is_set(flag)))?;
+
+        let weather = manifest.weathers.get(&rule.weather);
+        if weather.is_none() {
+            error!("no weather with id {:?}", rule.weather);
+        }
+        weather
+    }
+
+    fn is_set(&self, flag: StoryFlag) -> bool {
+        match flag {
+            StoryFlag::DroughtRelieved => self.prince.relieved_drought() == Some(true),
+            StoryFlag::DroughtIgnored => self.prince.relieved_drought() == Some(false),
+        }
+    }
+}
+
+/// The canvas grade and crowd pace of the current weather.
+#[derive(Resource)]
+struct WeatherMood {
+    grade: Color,
+    crowd_pace: f32,
+}
+
+impl Default for WeatherMood {
+    fn default() -> Self {
+        Self {
+            grade: Color::WHITE,
+            crowd_pace: 1.,
+        }
+    }
+}
+
+/// Shows the season's weather along with the court's day scenery, and clears it for every
+/// other scene. Scenery changes while the screen is black, so the weather changes at once.
+fn follow_scenery(
+    mut commands: Commands,
+    forecast: Forecast,
+    mut particles: Particles,
+    mut reader: EventReader<ShowScenery>,
+    mut ambience: EventWriter<AmbienceEvent>,
+    mut mood: ResMut<WeatherMood>,
+    prev_particles: Query<Entity, With<BackgroundParticles>>,
+) {
+    let Some(ShowScenery(kind)) = reader.read().last() else {
+        return;
+    };
+
+    let weather = if *kind == SceneryKind::COURT_DAY {
+        // Last night's particles go even when the forecast is clear.
+        for entity in prev_particles.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        forecast.weather()
+    } else {
+        None
+    };
+
+    let loops = weather.map(|w| w.ambience.clone()).unwrap_or_default();
+    ambience.send(AmbienceEvent::CrossfadeWeather(loops, WEATHER_FADE_SECS));
+
+    *mood = weather.map_or_else(WeatherMood::default, |weather| {
+        let grade = hex_to_vec4(weather.grade);
+        WeatherMood {
+            grade: Color::srgb(grade.x, grade.y, grade.z),
+            crowd_pace: weather.crowd_pace,
+        }
+    });
+
+    let Some(preset) = weather.and_then(|w| w.particles.as_deref()) else {
+        return;
+    };
+    if let Some(mut entity) = particles.spawn(&mut commands, preset, Transform::default()) {
+        entity.insert((ParallaxSprite(0.0045), BackgroundParticles));
+    }
+}
+
+fn apply_weather_mood(
+    mood: Res<WeatherMood>,
+    mut grade: ResMut<CanvasGrade>,
+    mut pace: ResMut<SceneryPace>,
+) {
+    grade.0 = mood.grade;
+    pace.0 = mood.crowd_pace;
+}
+
+fn clear_weather(mut mood: ResMut<WeatherMood>) {
+    *mood = WeatherMood::default();
+}